@@ -17,6 +17,20 @@ pub enum GameAssetError {
     AssetAlreadyRegistered = 5,
     /// The attempted operation is not allowed
     OperationNotAllowed = 6,
+    /// The requested resource does not exist
+    ResourceNotFound = 7,
+    /// The resource ID already exists
+    ResourceAlreadyExists = 8,
+    /// The holder's balance or edition count is too low for this action
+    InsufficientBalance = 9,
+    /// The spender's allowance is too low for this action
+    InsufficientAllowance = 10,
+    /// The requested edition does not exist
+    EditionNotFound = 11,
+    /// Minting would take the edition past its maximum supply
+    EditionSupplyExceeded = 12,
+    /// The asset ID belonged to an asset that has been burned
+    AssetBurned = 13,
 }
 
 /// Implementation to convert ConversionError to GameAssetError
@@ -1,5 +1,8 @@
-use crate::types::{AssetMetadata, GameAsset, OwnershipRecord};
-use soroban_sdk::{Address, BytesN, Env, Symbol};
+use crate::types::{
+    AssetApproval, AssetMetadata, Edition, EditionTransferRecord, GameAsset, OwnershipRecord,
+    ResourceInfo, Role,
+};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Symbol};
 
 /// Contract event emissions
 pub struct GameAssetEvents;
@@ -53,6 +56,48 @@ impl GameAssetEvents {
         env.events().publish(topics, data);
     }
 
+    /// Emits event when a role is granted
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `role` - Role that was granted
+    /// * `account` - Address receiving the role
+    /// * `item_type` - Item type the role is limited to, if any
+    pub fn emit_role_granted(env: &Env, role: Role, account: &Address, item_type: &Option<Bytes>) {
+        let topics = (Symbol::new(env, "role_granted"), role, account);
+        env.events().publish(topics, item_type.clone());
+    }
+
+    /// Emits event when a role is revoked
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `role` - Role that was revoked
+    /// * `account` - Address losing the role
+    /// * `item_type` - Item type the role was limited to, if any
+    pub fn emit_role_revoked(env: &Env, role: Role, account: &Address, item_type: &Option<Bytes>) {
+        let topics = (Symbol::new(env, "role_revoked"), role, account);
+        env.events().publish(topics, item_type.clone());
+    }
+
+    /// Emits event when an asset is burned
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    /// * `burned_by` - Address of the owner or admin who burned it
+    /// * `record` - The final ownership record of the asset
+    pub fn emit_asset_burned(
+        env: &Env,
+        asset_id: &BytesN<32>,
+        burned_by: &Address,
+        record: &OwnershipRecord,
+    ) {
+        let topics = (Symbol::new(env, "asset_burned"), asset_id);
+        let data = (burned_by.clone(), record.clone());
+        env.events().publish(topics, data);
+    }
+
     /// Emits event when asset metadata is updated
     ///
     /// # Arguments
@@ -82,4 +127,165 @@ impl GameAssetEvents {
         let data = (old_admin, new_admin);
         env.events().publish(topics, data);
     }
+
+    /// Emits event when a spender is approved to transfer an asset
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    /// * `owner` - Address of the owner
+    /// * `approval` - The approved spender and expiration ledger
+    pub fn emit_asset_approved(
+        env: &Env,
+        asset_id: &BytesN<32>,
+        owner: &Address,
+        approval: &AssetApproval,
+    ) {
+        let topics = (Symbol::new(env, "asset_approved"), asset_id, owner);
+        env.events().publish(topics, approval.clone());
+    }
+
+    /// Emits event when the approval on an asset is revoked
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    /// * `owner` - Address of the owner
+    pub fn emit_approval_revoked(env: &Env, asset_id: &BytesN<32>, owner: &Address) {
+        let topics = (Symbol::new(env, "approval_revoked"), asset_id);
+        env.events().publish(topics, owner);
+    }
+
+    /// Emits event when an operator is granted or denied access to all of an owner's assets
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `owner` - Address of the owner
+    /// * `operator` - Address of the operator
+    /// * `approved` - Whether the operator is now approved
+    pub fn emit_approval_for_all(env: &Env, owner: &Address, operator: &Address, approved: bool) {
+        let topics = (Symbol::new(env, "approval_for_all"), owner, operator);
+        env.events().publish(topics, approved);
+    }
+
+    /// Emits event when an edition is created
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition` - The edition that was created
+    pub fn emit_edition_created(env: &Env, edition: &Edition) {
+        let topics = (Symbol::new(env, "edition_created"), edition.id.clone());
+        env.events().publish(topics, edition.clone());
+    }
+
+    /// Emits event when copies of an edition are minted
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `to` - Address receiving the copies
+    /// * `quantity` - Number of copies minted
+    pub fn emit_edition_minted(env: &Env, edition_id: &BytesN<32>, to: &Address, quantity: u32) {
+        let topics = (Symbol::new(env, "edition_minted"), edition_id, to);
+        env.events().publish(topics, quantity);
+    }
+
+    /// Emits event when copies of an edition are transferred
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `record` - The transfer record
+    pub fn emit_edition_transferred(
+        env: &Env,
+        edition_id: &BytesN<32>,
+        record: &EditionTransferRecord,
+    ) {
+        let topics = (Symbol::new(env, "edition_transferred"), edition_id);
+        env.events().publish(topics, record.clone());
+    }
+
+    /// Emits event when a fungible resource is created
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource` - The resource that was created
+    pub fn emit_resource_created(env: &Env, resource: &ResourceInfo) {
+        let topics = (Symbol::new(env, "resource_created"), resource.id.clone());
+        env.events().publish(topics, resource.clone());
+    }
+
+    /// Emits event when units of a resource are minted
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `to` - Address receiving the units
+    /// * `amount` - Amount minted
+    pub fn emit_resource_minted(env: &Env, resource_id: &BytesN<32>, to: &Address, amount: i128) {
+        let topics = (Symbol::new(env, "resource_minted"), resource_id, to);
+        env.events().publish(topics, amount);
+    }
+
+    /// Emits event when units of a resource are burned
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address the units were burned from
+    /// * `amount` - Amount burned
+    pub fn emit_resource_burned(env: &Env, resource_id: &BytesN<32>, from: &Address, amount: i128) {
+        let topics = (Symbol::new(env, "resource_burned"), resource_id, from);
+        env.events().publish(topics, amount);
+    }
+
+    /// Emits event when units of a resource change hands
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the sender
+    /// * `to` - Address of the recipient
+    /// * `amount` - Amount transferred
+    pub fn emit_resource_transferred(
+        env: &Env,
+        resource_id: &BytesN<32>,
+        from: &Address,
+        to: &Address,
+        amount: i128,
+    ) {
+        let topics = (
+            Symbol::new(env, "resource_transferred"),
+            resource_id,
+            from,
+            to,
+        );
+        env.events().publish(topics, amount);
+    }
+
+    /// Emits event when a resource allowance is set
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the holder
+    /// * `spender` - Address allowed to spend
+    /// * `amount` - Allowed amount
+    /// * `expiration_ledger` - Last ledger the allowance is valid for
+    pub fn emit_resource_approved(
+        env: &Env,
+        resource_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) {
+        let topics = (
+            Symbol::new(env, "resource_approved"),
+            resource_id,
+            from,
+            spender,
+        );
+        env.events().publish(topics, (amount, expiration_ledger));
+    }
 }
@@ -1,7 +1,10 @@
 use soroban_sdk::{Address, Bytes, BytesN, Env, Map, Vec};
 
 use crate::errors::GameAssetError;
-use crate::types::{AssetMetadata, GameAsset, OwnershipRecord};
+use crate::types::{
+    AssetApproval, AssetMetadata, Edition, EditionTransferRecord, GameAsset, OwnershipRecord,
+    ResourceInfo, Role,
+};
 
 /// Interface for the Game Asset Registry contract.
 pub trait GameAssetTrait {
@@ -37,10 +40,63 @@ pub trait GameAssetTrait {
     /// * `Result<Address, GameAssetError>` - Admin address if initialized, Error otherwise
     fn get_admin(env: Env) -> Result<Address, GameAssetError>;
 
+    /// Grant a role to an address
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `role` - Role to grant
+    /// * `account` - Address receiving the role
+    /// * `item_type` - Item type to limit the role to, or None for all types
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful
+    ///
+    /// # Authentication
+    /// * Requires authorization from admin
+    fn grant_role(
+        env: Env,
+        role: Role,
+        account: Address,
+        item_type: Option<Bytes>,
+    ) -> Result<(), GameAssetError>;
+
+    /// Revoke a role from an address
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `role` - Role to revoke
+    /// * `account` - Address losing the role
+    /// * `item_type` - Item type the grant was limited to, or None for the unscoped grant
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful
+    ///
+    /// # Authentication
+    /// * Requires authorization from admin
+    fn revoke_role(
+        env: Env,
+        role: Role,
+        account: Address,
+        item_type: Option<Bytes>,
+    ) -> Result<(), GameAssetError>;
+
+    /// Check whether an address holds a role covering an item type
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `role` - Role to check
+    /// * `account` - Address to check
+    /// * `item_type` - Item type being acted on
+    ///
+    /// # Returns
+    /// * `bool` - true if the role is held for that type or for all types
+    fn has_role(env: Env, role: Role, account: Address, item_type: Bytes) -> bool;
+
     /// Register a new asset
     ///
     /// # Arguments
     /// * `env` - The contract environment
+    /// * `minter` - Address holding the Minter role for `item_type`
     /// * `owner` - Address of the initial owner
     /// * `asset_id` - Unique identifier for the asset
     /// * `name` - Name of the asset
@@ -51,9 +107,10 @@ pub trait GameAssetTrait {
     /// * `Result<(), GameAssetError>` - Ok if successful, Error if invalid or already exists
     ///
     /// # Authentication
-    /// * Requires authorization from admin
+    /// * Requires authorization from `minter`
     fn register_asset(
         env: Env,
+        minter: Address,
         owner: Address,
         asset_id: BytesN<32>,
         name: Bytes,
@@ -83,6 +140,143 @@ pub trait GameAssetTrait {
         reason: Bytes,
     ) -> Result<(), GameAssetError>;
 
+    /// Burn an asset, removing it from every index and reserving its ID
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `user` - Owner or admin burning the asset
+    /// * `asset_id` - Unique identifier for the asset
+    /// * `reason` - Reason for the burn, kept in the final ownership record
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or unauthorized
+    ///
+    /// # Authentication
+    /// * Requires authorization from admin or current owner
+    /// * The admin cannot burn an asset held by a contract, such as a marketplace holding it
+    ///   in custody; that contract has to release it or burn it itself
+    fn burn_asset(
+        env: Env,
+        user: Address,
+        asset_id: BytesN<32>,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError>;
+
+    /// Check if an asset ID belonged to a burned asset
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `asset_id` - Unique identifier for the asset
+    ///
+    /// # Returns
+    /// * `bool` - true if the asset was burned, false otherwise
+    fn is_burned(env: Env, asset_id: BytesN<32>) -> bool;
+
+    /// Approve a spender to transfer one asset, replacing any earlier approval
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Current owner address
+    /// * `spender` - Address allowed to transfer the asset
+    /// * `asset_id` - Unique identifier for the asset
+    /// * `expiration_ledger` - Last ledger the approval is valid for
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found, unauthorized or already expired
+    ///
+    /// # Authentication
+    /// * Requires authorization from current owner
+    fn approve(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        asset_id: BytesN<32>,
+        expiration_ledger: u32,
+    ) -> Result<(), GameAssetError>;
+
+    /// Revoke the approval on an asset
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Current owner address
+    /// * `asset_id` - Unique identifier for the asset
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or unauthorized
+    ///
+    /// # Authentication
+    /// * Requires authorization from current owner
+    fn revoke_approval(
+        env: Env,
+        owner: Address,
+        asset_id: BytesN<32>,
+    ) -> Result<(), GameAssetError>;
+
+    /// Grant or revoke an operator's right to transfer all of the owner's assets
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Address of the owner
+    /// * `operator` - Address of the operator
+    /// * `approved` - Whether the operator is approved
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if the operator is the owner
+    ///
+    /// # Authentication
+    /// * Requires authorization from the owner
+    fn set_approval_for_all(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), GameAssetError>;
+
+    /// Transfer an asset on the owner's behalf
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `spender` - Approved spender or operator
+    /// * `from` - Current owner address
+    /// * `to` - New owner address
+    /// * `asset_id` - Unique identifier for the asset
+    /// * `reason` - Reason for the transfer
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or not approved
+    ///
+    /// # Authentication
+    /// * Requires authorization from `spender`
+    fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        asset_id: BytesN<32>,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError>;
+
+    /// Get the live approval on an asset
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `asset_id` - Unique identifier for the asset
+    ///
+    /// # Returns
+    /// * `Option<AssetApproval>` - Approved spender and expiration, if any
+    fn get_approved(env: Env, asset_id: BytesN<32>) -> Option<AssetApproval>;
+
+    /// Check whether an operator may transfer all of an owner's assets
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Address of the owner
+    /// * `operator` - Address of the operator
+    ///
+    /// # Returns
+    /// * `bool` - true if approved, false otherwise
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
+
     /// Update metadata for an asset
     ///
     /// # Arguments
@@ -96,7 +290,8 @@ pub trait GameAssetTrait {
     /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or unauthorized
     ///
     /// # Authentication
-    /// * Requires authorization from admin or current owner
+    /// * Requires authorization from admin, current owner, or a MetadataEditor covering
+    ///   both the current and the new item type
     fn update_metadata(
         env: Env,
         user: Address,
@@ -159,6 +354,32 @@ pub trait GameAssetTrait {
     /// * `Vec<GameAsset>` - Collection of assets, empty if none found
     fn get_user_assets(env: Env, owner: Address) -> Vec<GameAsset>;
 
+    /// Get a page of the assets owned by a user
+    ///
+    /// Positions aren't stable: removing an asset moves the owner's last asset into
+    /// its place. Custody holders such as a marketplace should page rather than
+    /// call `get_user_assets`
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Address of the owner
+    /// * `skip` - Number of assets to skip (for pagination)
+    /// * `limit` - Maximum number of assets to return
+    ///
+    /// # Returns
+    /// * `Vec<GameAsset>` - Collection of assets, empty if none found
+    fn get_user_assets_page(env: Env, owner: Address, skip: u32, limit: u32) -> Vec<GameAsset>;
+
+    /// Get the number of assets owned by a user
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `owner` - Address of the owner
+    ///
+    /// # Returns
+    /// * `u32` - Number of assets owned, zero if none
+    fn get_user_asset_count(env: Env, owner: Address) -> u32;
+
     /// Get all registered asset IDs
     ///
     /// # Arguments
@@ -189,3 +410,293 @@ pub trait GameAssetTrait {
     /// * `u32` - Total number of registered assets
     fn get_asset_count(env: Env) -> u32;
 }
+
+/// Interface for limited editions: many identical copies sharing one metadata template.
+pub trait EditionTrait {
+    /// Create a new edition with no copies minted
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `minter` - Address holding the Minter role for `item_type`
+    /// * `edition_id` - Unique identifier for the edition
+    /// * `name` - Name of the edition
+    /// * `item_type` - Type of the item
+    /// * `attributes` - Additional attributes as key-value pairs
+    /// * `max_supply` - Maximum number of copies that can be minted
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if invalid or already exists
+    ///
+    /// # Authentication
+    /// * Requires authorization from `minter`
+    fn create_edition(
+        env: Env,
+        minter: Address,
+        edition_id: BytesN<32>,
+        name: Bytes,
+        item_type: Bytes,
+        attributes: Map<Bytes, Bytes>,
+        max_supply: u32,
+    ) -> Result<(), GameAssetError>;
+
+    /// Mint copies of an edition to a holder
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `minter` - Address holding the Minter role for the edition's item type
+    /// * `edition_id` - Identifier of the edition
+    /// * `to` - Address receiving the copies
+    /// * `quantity` - Number of copies to mint
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or supply exceeded
+    ///
+    /// # Authentication
+    /// * Requires authorization from `minter`
+    fn mint_edition(
+        env: Env,
+        minter: Address,
+        edition_id: BytesN<32>,
+        to: Address,
+        quantity: u32,
+    ) -> Result<(), GameAssetError>;
+
+    /// Transfer copies of an edition
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `from` - Current holder address
+    /// * `to` - New holder address
+    /// * `edition_id` - Identifier of the edition
+    /// * `quantity` - Number of copies to transfer
+    /// * `reason` - Reason for the transfer
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or too few copies held
+    ///
+    /// # Authentication
+    /// * Requires authorization from `from`
+    fn transfer_edition(
+        env: Env,
+        from: Address,
+        to: Address,
+        edition_id: BytesN<32>,
+        quantity: u32,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError>;
+
+    /// Get the number of copies of an edition a holder owns
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `owner` - Address of the holder
+    ///
+    /// # Returns
+    /// * `u32` - Number of copies, zero if none
+    fn edition_balance(env: Env, edition_id: BytesN<32>, owner: Address) -> u32;
+
+    /// Get detailed information about an edition
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `edition_id` - Identifier of the edition
+    ///
+    /// # Returns
+    /// * `Result<Edition, GameAssetError>` - Edition if found, Error if not found
+    fn get_edition(env: Env, edition_id: BytesN<32>) -> Result<Edition, GameAssetError>;
+
+    /// Get a page of the transfer history of an edition, oldest first
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `start` - Position of the first record to return
+    /// * `limit` - Maximum number of records to return
+    ///
+    /// # Returns
+    /// * `Result<Vec<EditionTransferRecord>, GameAssetError>` - History records if found, Error if not found
+    fn get_edition_history(
+        env: Env,
+        edition_id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<EditionTransferRecord>, GameAssetError>;
+}
+
+/// Interface for fungible, SEP-41-style resources held in the same registry.
+pub trait FungibleResourceTrait {
+    /// Create a new fungible resource with no supply
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `minter` - Address holding an unscoped Minter role
+    /// * `resource_id` - Unique identifier for the resource
+    /// * `name` - Name of the resource
+    /// * `decimals` - Number of decimal places balances are expressed in
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if invalid or already exists
+    ///
+    /// # Authentication
+    /// * Requires authorization from `minter`
+    fn create_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        name: Bytes,
+        decimals: u32,
+    ) -> Result<(), GameAssetError>;
+
+    /// Mint units of a resource to a holder
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `minter` - Address holding an unscoped Minter role
+    /// * `resource_id` - Identifier of the resource
+    /// * `to` - Address receiving the units
+    /// * `amount` - Amount to mint
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or invalid amount
+    ///
+    /// # Authentication
+    /// * Requires authorization from `minter`
+    fn mint_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError>;
+
+    /// Burn units of a resource from a holder, e.g. when a consumable is used
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `minter` - Address holding an unscoped Minter role
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address to burn from
+    /// * `amount` - Amount to burn
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or balance too low
+    ///
+    /// # Authentication
+    /// * Requires authorization from `minter`
+    fn burn_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError>;
+
+    /// Transfer units of a resource
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the sender
+    /// * `to` - Address of the recipient
+    /// * `amount` - Amount to transfer
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or balance too low
+    ///
+    /// # Authentication
+    /// * Requires authorization from `from`
+    fn transfer_resource(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError>;
+
+    /// Allow a spender to transfer part of the caller's balance, replacing any earlier allowance
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the holder
+    /// * `spender` - Address allowed to spend
+    /// * `amount` - Allowed amount, zero to revoke
+    /// * `expiration_ledger` - Last ledger the allowance is valid for
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if not found or invalid input
+    ///
+    /// # Authentication
+    /// * Requires authorization from `from`
+    fn approve_resource(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), GameAssetError>;
+
+    /// Transfer units of a resource using an allowance
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `spender` - Address spending the allowance
+    /// * `from` - Address of the holder
+    /// * `to` - Address of the recipient
+    /// * `amount` - Amount to transfer
+    ///
+    /// # Returns
+    /// * `Result<(), GameAssetError>` - Ok if successful, Error if allowance or balance too low
+    ///
+    /// # Authentication
+    /// * Requires authorization from `spender`
+    fn transfer_resource_from(
+        env: Env,
+        resource_id: BytesN<32>,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError>;
+
+    /// Get a holder's balance of a resource
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `holder` - Address of the holder
+    ///
+    /// # Returns
+    /// * `i128` - Balance, zero if none
+    fn resource_balance(env: Env, resource_id: BytesN<32>, holder: Address) -> i128;
+
+    /// Get the amount a spender may still transfer from a holder
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the holder
+    /// * `spender` - Address of the spender
+    ///
+    /// # Returns
+    /// * `i128` - Remaining allowance, zero if none or expired
+    fn resource_allowance(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+    ) -> i128;
+
+    /// Get information about a resource
+    ///
+    /// # Arguments
+    /// * `env` - The contract environment
+    /// * `resource_id` - Identifier of the resource
+    ///
+    /// # Returns
+    /// * `Result<ResourceInfo, GameAssetError>` - Resource if found, Error if not found
+    fn get_resource(env: Env, resource_id: BytesN<32>) -> Result<ResourceInfo, GameAssetError>;
+}
@@ -6,16 +6,20 @@ mod interface;
 mod storage;
 mod types;
 
-// #[cfg(test)]
-// mod test;
+#[cfg(test)]
+mod test;
 
 use errors::GameAssetError;
 use events::GameAssetEvents;
-use interface::GameAssetTrait;
+use interface::{EditionTrait, FungibleResourceTrait, GameAssetTrait};
 use storage::GameAssetStorage;
-use types::{AssetMetadata, GameAsset, OwnershipRecord};
+pub use types::Role;
+use types::{
+    AllowanceValue, AssetApproval, AssetMetadata, Edition, EditionTransferRecord, GameAsset,
+    OwnershipRecord, ResourceInfo,
+};
 
-use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, Map, Vec};
+use soroban_sdk::{contract, contractimpl, xdr::ToXdr, Address, Bytes, BytesN, Env, Map, Vec};
 
 #[contract]
 pub struct GameAssetContract;
@@ -61,28 +65,76 @@ impl GameAssetTrait for GameAssetContract {
         Ok(GameAssetStorage::get_admin(&env))
     }
 
+    fn grant_role(
+        env: Env,
+        role: Role,
+        account: Address,
+        item_type: Option<Bytes>,
+    ) -> Result<(), GameAssetError> {
+        // Get and authorize admin
+        let admin = GameAssetStorage::get_admin(&env);
+        admin.require_auth();
+
+        GameAssetStorage::set_role(&env, role, &account, &item_type, true);
+
+        GameAssetEvents::emit_role_granted(&env, role, &account, &item_type);
+
+        Ok(())
+    }
+
+    fn revoke_role(
+        env: Env,
+        role: Role,
+        account: Address,
+        item_type: Option<Bytes>,
+    ) -> Result<(), GameAssetError> {
+        // Get and authorize admin
+        let admin = GameAssetStorage::get_admin(&env);
+        admin.require_auth();
+
+        GameAssetStorage::set_role(&env, role, &account, &item_type, false);
+
+        GameAssetEvents::emit_role_revoked(&env, role, &account, &item_type);
+
+        Ok(())
+    }
+
+    fn has_role(env: Env, role: Role, account: Address, item_type: Bytes) -> bool {
+        GameAssetStorage::has_role(&env, role, &account, &item_type)
+    }
+
     fn register_asset(
         env: Env,
+        minter: Address,
         owner: Address,
         asset_id: BytesN<32>,
         name: Bytes,
         item_type: Bytes,
         attributes: Map<Bytes, Bytes>,
     ) -> Result<(), GameAssetError> {
-        // Get and authorize admin
-        let admin = GameAssetStorage::get_admin(&env);
-        admin.require_auth();
+        // Require a Minter role covering this item type
+        if !GameAssetStorage::has_role(&env, Role::Minter, &minter, &item_type) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
 
         // Validate input
         if name.len() == 0 || item_type.len() == 0 {
             return Err(GameAssetError::InvalidInput);
         }
 
-        // Check if asset already exists
-        if GameAssetStorage::has_asset(&env, &asset_id) {
+        // Check if asset or an edition with this ID already exists
+        if GameAssetStorage::has_asset(&env, &asset_id)
+            || GameAssetStorage::get_edition(&env, &asset_id).is_some()
+        {
             return Err(GameAssetError::AssetAlreadyRegistered);
         }
 
+        // Burned IDs stay reserved
+        if GameAssetStorage::is_burned(&env, &asset_id) {
+            return Err(GameAssetError::AssetBurned);
+        }
+
         // Create metadata
         let metadata = AssetMetadata {
             name,
@@ -135,41 +187,200 @@ impl GameAssetTrait for GameAssetContract {
         }
 
         // Get the asset
-        let mut asset = GameAssetStorage::get_asset(&env, &asset_id);
+        let asset = GameAssetStorage::get_asset(&env, &asset_id);
 
         // Verify current owner
         if asset.owner != from {
             return Err(GameAssetError::Unauthorized);
         }
 
-        // Create ownership record
+        move_asset(&env, asset, &from, &to, reason);
+
+        Ok(())
+    }
+
+    fn burn_asset(
+        env: Env,
+        user: Address,
+        asset_id: BytesN<32>,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError> {
+        // Check if asset exists
+        if !GameAssetStorage::has_asset(&env, &asset_id) {
+            return Err(GameAssetError::AssetNotFound);
+        }
+
+        // Get the asset
+        let asset = GameAssetStorage::get_asset(&env, &asset_id);
+
+        // Get the admin
+        let admin = GameAssetStorage::get_admin(&env);
+
+        // Require authorization from either admin or owner
+        if (admin == user) || (asset.owner == user) {
+            user.require_auth();
+        } else {
+            return Err(GameAssetError::Unauthorized);
+        }
+
+        // A contract owner may be holding the asset in escrow (e.g. a marketplace listing);
+        // burning it from under that contract would strand whatever it escrowed against it
+        if asset.owner != user && is_contract_address(&env, &asset.owner) {
+            return Err(GameAssetError::OperationNotAllowed);
+        }
+
+        // Final ownership record, kept as the tombstone
         let record = OwnershipRecord {
-            previous_owner: from.clone(),
+            previous_owner: asset.owner.clone(),
             timestamp: env.ledger().timestamp(),
-            reason: reason.clone(),
+            reason,
         };
 
-        // Add to history
-        asset.history.push_back(record.clone());
+        // Drop the asset and every index entry pointing at it
+        GameAssetStorage::remove_asset(&env, &asset_id);
+        GameAssetStorage::remove_asset_approval(&env, &asset_id);
+        GameAssetStorage::remove_user_asset(&env, &asset.owner, &asset_id);
+        GameAssetStorage::remove_asset_id(&env, &asset_id);
+        GameAssetStorage::decrement_asset_count(&env);
 
-        // Update owner
-        asset.owner = to.clone();
+        // Reserve the ID so it can't be registered again
+        GameAssetStorage::set_burned(&env, &asset_id, &record);
 
-        // Update asset in storage
-        GameAssetStorage::set_asset(&env, &asset);
+        // Emit burn event
+        GameAssetEvents::emit_asset_burned(&env, &asset_id, &user, &record);
+
+        Ok(())
+    }
 
-        // Remove from previous owner's assets
-        GameAssetStorage::remove_user_asset(&env, &from, &asset_id);
+    fn is_burned(env: Env, asset_id: BytesN<32>) -> bool {
+        GameAssetStorage::is_burned(&env, &asset_id)
+    }
 
-        // Add to new owner's assets
-        GameAssetStorage::add_user_asset(&env, &to, &asset_id);
+    fn approve(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        asset_id: BytesN<32>,
+        expiration_ledger: u32,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the owner
+        owner.require_auth();
 
-        // Emit transfer event
-        GameAssetEvents::emit_asset_transferred(&env, &asset_id, &from, &to, &record);
+        // Check if asset exists
+        if !GameAssetStorage::has_asset(&env, &asset_id) {
+            return Err(GameAssetError::AssetNotFound);
+        }
+
+        // Verify current owner
+        if GameAssetStorage::get_asset(&env, &asset_id).owner != owner {
+            return Err(GameAssetError::Unauthorized);
+        }
+
+        // The approval must not already be expired
+        if expiration_ledger < env.ledger().sequence() {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        let approval = AssetApproval {
+            spender,
+            expiration_ledger,
+        };
+        GameAssetStorage::set_asset_approval(&env, &asset_id, &approval);
+
+        GameAssetEvents::emit_asset_approved(&env, &asset_id, &owner, &approval);
+
+        Ok(())
+    }
+
+    fn revoke_approval(
+        env: Env,
+        owner: Address,
+        asset_id: BytesN<32>,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the owner
+        owner.require_auth();
+
+        // Check if asset exists
+        if !GameAssetStorage::has_asset(&env, &asset_id) {
+            return Err(GameAssetError::AssetNotFound);
+        }
+
+        // Verify current owner
+        if GameAssetStorage::get_asset(&env, &asset_id).owner != owner {
+            return Err(GameAssetError::Unauthorized);
+        }
+
+        GameAssetStorage::remove_asset_approval(&env, &asset_id);
+
+        GameAssetEvents::emit_approval_revoked(&env, &asset_id, &owner);
+
+        Ok(())
+    }
+
+    fn set_approval_for_all(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the owner
+        owner.require_auth();
+
+        if owner == operator {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        GameAssetStorage::set_operator(&env, &owner, &operator, approved);
+
+        GameAssetEvents::emit_approval_for_all(&env, &owner, &operator, approved);
+
+        Ok(())
+    }
+
+    fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        asset_id: BytesN<32>,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the spender
+        spender.require_auth();
+
+        // Check if asset exists
+        if !GameAssetStorage::has_asset(&env, &asset_id) {
+            return Err(GameAssetError::AssetNotFound);
+        }
+
+        // Get the asset
+        let asset = GameAssetStorage::get_asset(&env, &asset_id);
+
+        // Verify current owner
+        if asset.owner != from {
+            return Err(GameAssetError::Unauthorized);
+        }
+
+        // The spender needs a live approval on this asset or operator rights over the owner
+        let approved = GameAssetStorage::get_asset_approval(&env, &asset_id)
+            .is_some_and(|x| x.spender == spender);
+        if !approved && !GameAssetStorage::is_operator(&env, &from, &spender) {
+            return Err(GameAssetError::Unauthorized);
+        }
+
+        move_asset(&env, asset, &from, &to, reason);
 
         Ok(())
     }
 
+    fn get_approved(env: Env, asset_id: BytesN<32>) -> Option<AssetApproval> {
+        GameAssetStorage::get_asset_approval(&env, &asset_id)
+    }
+
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        GameAssetStorage::is_operator(&env, &owner, &operator)
+    }
+
     fn update_metadata(
         env: Env,
         user: Address,
@@ -189,10 +400,15 @@ impl GameAssetTrait for GameAssetContract {
         // Get the admin
         let admin = GameAssetStorage::get_admin(&env);
 
-        // Require authorization from either admin or owner
+        // Editors must be allowed to touch both the old and the new item type
+        let old_type = &asset.metadata.item_type;
+        let is_editor = GameAssetStorage::has_role(&env, Role::MetadataEditor, &user, old_type)
+            && GameAssetStorage::has_role(&env, Role::MetadataEditor, &user, &item_type);
+
+        // Require authorization from admin, owner or a metadata editor
 
         // Do we want to allow the admin to update the metadata of any asset?
-        if (admin == user) || (asset.owner == user) {
+        if (admin == user) || (asset.owner == user) || is_editor {
             user.require_auth();
         } else {
             return Err(GameAssetError::Unauthorized);
@@ -262,23 +478,34 @@ impl GameAssetTrait for GameAssetContract {
     }
 
     fn get_user_assets(env: Env, owner: Address) -> Vec<GameAsset> {
-        // Get the asset IDs for this user
-        let asset_ids = GameAssetStorage::get_user_assets(&env, &owner);
+        let count = GameAssetStorage::get_user_asset_count(&env, &owner);
+
+        Self::get_user_assets_page(env, owner, 0, count)
+    }
+
+    fn get_user_assets_page(env: Env, owner: Address, skip: u32, limit: u32) -> Vec<GameAsset> {
+        let count = GameAssetStorage::get_user_asset_count(&env, &owner);
+        let end = skip.saturating_add(limit).min(count);
 
         // Create a vector to hold the assets
         let mut assets = Vec::new(&env);
 
         // Fetch each asset
-        for id in asset_ids.iter() {
-            if GameAssetStorage::has_asset(&env, &id) {
-                let asset = GameAssetStorage::get_asset(&env, &id);
-                assets.push_back(asset);
+        for index in skip..end {
+            if let Some(id) = GameAssetStorage::get_user_asset_at(&env, &owner, index) {
+                if GameAssetStorage::has_asset(&env, &id) {
+                    assets.push_back(GameAssetStorage::get_asset(&env, &id));
+                }
             }
         }
 
         assets
     }
 
+    fn get_user_asset_count(env: Env, owner: Address) -> u32 {
+        GameAssetStorage::get_user_asset_count(&env, &owner)
+    }
+
     fn get_all_assets(env: Env, skip: u32, limit: u32) -> Vec<BytesN<32>> {
         // Get all asset IDs
         let all_ids = GameAssetStorage::get_all_asset_ids(&env);
@@ -312,3 +539,443 @@ impl GameAssetTrait for GameAssetContract {
         GameAssetStorage::get_asset_count(&env)
     }
 }
+
+#[contractimpl]
+impl EditionTrait for GameAssetContract {
+    fn create_edition(
+        env: Env,
+        minter: Address,
+        edition_id: BytesN<32>,
+        name: Bytes,
+        item_type: Bytes,
+        attributes: Map<Bytes, Bytes>,
+        max_supply: u32,
+    ) -> Result<(), GameAssetError> {
+        // Require a Minter role covering this item type
+        if !GameAssetStorage::has_role(&env, Role::Minter, &minter, &item_type) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
+
+        // Validate input
+        if name.is_empty() || item_type.is_empty() || max_supply == 0 {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        // Editions share the ID space with unique assets
+        if GameAssetStorage::get_edition(&env, &edition_id).is_some()
+            || GameAssetStorage::has_asset(&env, &edition_id)
+        {
+            return Err(GameAssetError::AssetAlreadyRegistered);
+        }
+        if GameAssetStorage::is_burned(&env, &edition_id) {
+            return Err(GameAssetError::AssetBurned);
+        }
+
+        let edition = Edition {
+            id: edition_id,
+            metadata: AssetMetadata {
+                name,
+                item_type,
+                attributes,
+            },
+            max_supply,
+            minted: 0,
+            transfer_count: 0,
+        };
+
+        GameAssetStorage::set_edition(&env, &edition);
+
+        GameAssetEvents::emit_edition_created(&env, &edition);
+
+        Ok(())
+    }
+
+    fn mint_edition(
+        env: Env,
+        minter: Address,
+        edition_id: BytesN<32>,
+        to: Address,
+        quantity: u32,
+    ) -> Result<(), GameAssetError> {
+        if quantity == 0 {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        let mut edition = get_edition_or_err(&env, &edition_id)?;
+
+        // Require a Minter role covering the edition's item type
+        if !GameAssetStorage::has_role(&env, Role::Minter, &minter, &edition.metadata.item_type) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
+
+        // Never mint past the cap
+        let minted = edition
+            .minted
+            .checked_add(quantity)
+            .filter(|x| *x <= edition.max_supply)
+            .ok_or(GameAssetError::EditionSupplyExceeded)?;
+
+        edition.minted = minted;
+        GameAssetStorage::set_edition(&env, &edition);
+
+        let balance = GameAssetStorage::get_edition_balance(&env, &edition_id, &to);
+        GameAssetStorage::set_edition_balance(&env, &edition_id, &to, balance + quantity);
+
+        GameAssetEvents::emit_edition_minted(&env, &edition_id, &to, quantity);
+
+        Ok(())
+    }
+
+    fn transfer_edition(
+        env: Env,
+        from: Address,
+        to: Address,
+        edition_id: BytesN<32>,
+        quantity: u32,
+        reason: Bytes,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from current holder
+        from.require_auth();
+
+        if quantity == 0 {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        let mut edition = get_edition_or_err(&env, &edition_id)?;
+
+        // Verify the holder has enough copies
+        let from_balance = GameAssetStorage::get_edition_balance(&env, &edition_id, &from);
+        if from_balance < quantity {
+            return Err(GameAssetError::InsufficientBalance);
+        }
+
+        GameAssetStorage::set_edition_balance(&env, &edition_id, &from, from_balance - quantity);
+        let to_balance = GameAssetStorage::get_edition_balance(&env, &edition_id, &to);
+        GameAssetStorage::set_edition_balance(&env, &edition_id, &to, to_balance + quantity);
+
+        // Create transfer record
+        let record = EditionTransferRecord {
+            from: from.clone(),
+            to: to.clone(),
+            quantity,
+            timestamp: env.ledger().timestamp(),
+            reason,
+        };
+
+        // Add to history; each record gets its own entry so the edition itself stays small
+        GameAssetStorage::set_edition_transfer(&env, &edition_id, edition.transfer_count, &record);
+        edition.transfer_count += 1;
+        GameAssetStorage::set_edition(&env, &edition);
+
+        // Emit transfer event
+        GameAssetEvents::emit_edition_transferred(&env, &edition_id, &record);
+
+        Ok(())
+    }
+
+    fn edition_balance(env: Env, edition_id: BytesN<32>, owner: Address) -> u32 {
+        GameAssetStorage::get_edition_balance(&env, &edition_id, &owner)
+    }
+
+    fn get_edition(env: Env, edition_id: BytesN<32>) -> Result<Edition, GameAssetError> {
+        get_edition_or_err(&env, &edition_id)
+    }
+
+    fn get_edition_history(
+        env: Env,
+        edition_id: BytesN<32>,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<EditionTransferRecord>, GameAssetError> {
+        let edition = get_edition_or_err(&env, &edition_id)?;
+
+        let end = start.saturating_add(limit).min(edition.transfer_count);
+        let mut records = Vec::new(&env);
+        for index in start..end {
+            if let Some(record) = GameAssetStorage::get_edition_transfer(&env, &edition_id, index) {
+                records.push_back(record);
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+#[contractimpl]
+impl FungibleResourceTrait for GameAssetContract {
+    fn create_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        name: Bytes,
+        decimals: u32,
+    ) -> Result<(), GameAssetError> {
+        // Resources have no item type, so only an unscoped Minter role covers them
+        if !GameAssetStorage::has_global_role(&env, Role::Minter, &minter) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
+
+        // Validate input
+        if name.is_empty() {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        // Check if resource already exists
+        if GameAssetStorage::get_resource(&env, &resource_id).is_some() {
+            return Err(GameAssetError::ResourceAlreadyExists);
+        }
+
+        let resource = ResourceInfo {
+            id: resource_id,
+            name,
+            decimals,
+            total_supply: 0,
+        };
+
+        GameAssetStorage::set_resource(&env, &resource);
+
+        GameAssetEvents::emit_resource_created(&env, &resource);
+
+        Ok(())
+    }
+
+    fn mint_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError> {
+        // Resources have no item type, so only an unscoped Minter role covers them
+        if !GameAssetStorage::has_global_role(&env, Role::Minter, &minter) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
+
+        if amount <= 0 {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        let mut resource = get_resource_or_err(&env, &resource_id)?;
+
+        // Credit the holder and grow the supply
+        let balance = GameAssetStorage::get_resource_balance(&env, &resource_id, &to);
+        GameAssetStorage::set_resource_balance(&env, &resource_id, &to, balance + amount);
+
+        resource.total_supply += amount;
+        GameAssetStorage::set_resource(&env, &resource);
+
+        GameAssetEvents::emit_resource_minted(&env, &resource_id, &to, amount);
+
+        Ok(())
+    }
+
+    fn burn_resource(
+        env: Env,
+        minter: Address,
+        resource_id: BytesN<32>,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError> {
+        // Resources have no item type, so only an unscoped Minter role covers them
+        if !GameAssetStorage::has_global_role(&env, Role::Minter, &minter) {
+            return Err(GameAssetError::Unauthorized);
+        }
+        minter.require_auth();
+
+        if amount <= 0 {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        let mut resource = get_resource_or_err(&env, &resource_id)?;
+
+        // Debit the holder and shrink the supply
+        let balance = GameAssetStorage::get_resource_balance(&env, &resource_id, &from);
+        if balance < amount {
+            return Err(GameAssetError::InsufficientBalance);
+        }
+        GameAssetStorage::set_resource_balance(&env, &resource_id, &from, balance - amount);
+
+        resource.total_supply -= amount;
+        GameAssetStorage::set_resource(&env, &resource);
+
+        GameAssetEvents::emit_resource_burned(&env, &resource_id, &from, amount);
+
+        Ok(())
+    }
+
+    fn transfer_resource(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the sender
+        from.require_auth();
+
+        move_resource(&env, &resource_id, &from, &to, amount)
+    }
+
+    fn approve_resource(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the holder
+        from.require_auth();
+
+        // A live allowance must not already be expired
+        if amount < 0 || (amount > 0 && expiration_ledger < env.ledger().sequence()) {
+            return Err(GameAssetError::InvalidInput);
+        }
+
+        get_resource_or_err(&env, &resource_id)?;
+
+        let allowance = AllowanceValue {
+            amount,
+            expiration_ledger,
+        };
+        GameAssetStorage::set_resource_allowance(&env, &resource_id, &from, &spender, &allowance);
+
+        GameAssetEvents::emit_resource_approved(
+            &env,
+            &resource_id,
+            &from,
+            &spender,
+            amount,
+            expiration_ledger,
+        );
+
+        Ok(())
+    }
+
+    fn transfer_resource_from(
+        env: Env,
+        resource_id: BytesN<32>,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), GameAssetError> {
+        // Require authorization from the spender
+        spender.require_auth();
+
+        // Spend down the allowance before moving the balance
+        let mut allowance =
+            GameAssetStorage::get_resource_allowance(&env, &resource_id, &from, &spender);
+        if allowance.amount < amount {
+            return Err(GameAssetError::InsufficientAllowance);
+        }
+        allowance.amount -= amount;
+        GameAssetStorage::set_resource_allowance(&env, &resource_id, &from, &spender, &allowance);
+
+        move_resource(&env, &resource_id, &from, &to, amount)
+    }
+
+    fn resource_balance(env: Env, resource_id: BytesN<32>, holder: Address) -> i128 {
+        GameAssetStorage::get_resource_balance(&env, &resource_id, &holder)
+    }
+
+    fn resource_allowance(
+        env: Env,
+        resource_id: BytesN<32>,
+        from: Address,
+        spender: Address,
+    ) -> i128 {
+        GameAssetStorage::get_resource_allowance(&env, &resource_id, &from, &spender).amount
+    }
+
+    fn get_resource(env: Env, resource_id: BytesN<32>) -> Result<ResourceInfo, GameAssetError> {
+        get_resource_or_err(&env, &resource_id)
+    }
+}
+
+/// Hands an asset to a new owner once the transfer has been authorized
+fn move_asset(env: &Env, mut asset: GameAsset, from: &Address, to: &Address, reason: Bytes) {
+    // Create ownership record
+    let record = OwnershipRecord {
+        previous_owner: from.clone(),
+        timestamp: env.ledger().timestamp(),
+        reason,
+    };
+
+    // Add to history
+    asset.history.push_back(record.clone());
+
+    // Update owner
+    asset.owner = to.clone();
+
+    // Update asset in storage
+    GameAssetStorage::set_asset(env, &asset);
+
+    // Approvals don't carry over to the new owner
+    GameAssetStorage::remove_asset_approval(env, &asset.id);
+
+    // Remove from previous owner's assets
+    GameAssetStorage::remove_user_asset(env, from, &asset.id);
+
+    // Add to new owner's assets
+    GameAssetStorage::add_user_asset(env, to, &asset.id);
+
+    // Emit transfer event
+    GameAssetEvents::emit_asset_transferred(env, &asset.id, from, to, &record);
+}
+
+/// Byte offset of the ScAddress discriminant in an address's XDR: it follows the
+/// four-byte ScVal::Address tag and is itself a big-endian u32
+const SC_ADDRESS_TYPE_BYTE: u32 = 7;
+
+/// ScAddress discriminant of a contract address; accounts are 0
+const SC_ADDRESS_TYPE_CONTRACT: u8 = 1;
+
+fn is_contract_address(env: &Env, address: &Address) -> bool {
+    address.clone().to_xdr(env).get(SC_ADDRESS_TYPE_BYTE) == Some(SC_ADDRESS_TYPE_CONTRACT)
+}
+
+/// Looks up an edition, failing if it was never created
+fn get_edition_or_err(env: &Env, edition_id: &BytesN<32>) -> Result<Edition, GameAssetError> {
+    GameAssetStorage::get_edition(env, edition_id).ok_or(GameAssetError::EditionNotFound)
+}
+
+/// Looks up a resource, failing if it was never created
+fn get_resource_or_err(
+    env: &Env,
+    resource_id: &BytesN<32>,
+) -> Result<ResourceInfo, GameAssetError> {
+    GameAssetStorage::get_resource(env, resource_id).ok_or(GameAssetError::ResourceNotFound)
+}
+
+/// Moves resource units between holders once the caller has been authorized
+fn move_resource(
+    env: &Env,
+    resource_id: &BytesN<32>,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), GameAssetError> {
+    if amount <= 0 {
+        return Err(GameAssetError::InvalidInput);
+    }
+
+    get_resource_or_err(env, resource_id)?;
+
+    let from_balance = GameAssetStorage::get_resource_balance(env, resource_id, from);
+    if from_balance < amount {
+        return Err(GameAssetError::InsufficientBalance);
+    }
+
+    GameAssetStorage::set_resource_balance(env, resource_id, from, from_balance - amount);
+    let to_balance = GameAssetStorage::get_resource_balance(env, resource_id, to);
+    GameAssetStorage::set_resource_balance(env, resource_id, to, to_balance + amount);
+
+    GameAssetEvents::emit_resource_transferred(env, resource_id, from, to, amount);
+
+    Ok(())
+}
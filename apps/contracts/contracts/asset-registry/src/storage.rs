@@ -1,5 +1,8 @@
-use crate::types::{DataKey, GameAsset};
-use soroban_sdk::{Address, BytesN, Env, Vec};
+use crate::types::{
+    AllowanceValue, AssetApproval, DataKey, Edition, EditionTransferRecord, GameAsset,
+    OwnershipRecord, ResourceInfo, Role,
+};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Vec};
 
 /// Storage operations for the Game Asset Registry contract
 pub struct GameAssetStorage;
@@ -27,6 +30,62 @@ impl GameAssetStorage {
         env.storage().instance().get(&key).unwrap()
     }
 
+    /// Grants or revokes a role
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `role` - Role to change
+    /// * `account` - Address the role applies to
+    /// * `item_type` - Item type the role is limited to, or None for all types
+    /// * `granted` - Whether the role is held
+    pub fn set_role(
+        env: &Env,
+        role: Role,
+        account: &Address,
+        item_type: &Option<Bytes>,
+        granted: bool,
+    ) {
+        let key = DataKey::Role(role, account.clone(), item_type.clone());
+        if granted {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Checks whether an address holds a role for an item type, either directly or
+    /// through an unscoped grant
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `role` - Role to check
+    /// * `account` - Address to check
+    /// * `item_type` - Item type being acted on
+    ///
+    /// # Returns
+    /// * `bool` - True if the role covers the item type, false otherwise
+    pub fn has_role(env: &Env, role: Role, account: &Address, item_type: &Bytes) -> bool {
+        let global = DataKey::Role(role, account.clone(), None);
+        let scoped = DataKey::Role(role, account.clone(), Some(item_type.clone()));
+
+        env.storage().persistent().has(&global) || env.storage().persistent().has(&scoped)
+    }
+
+    /// Checks whether an address holds a role through an unscoped grant, covering every
+    /// item type
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `role` - Role to check
+    /// * `account` - Address to check
+    ///
+    /// # Returns
+    /// * `bool` - True if the role is held for all item types, false otherwise
+    pub fn has_global_role(env: &Env, role: Role, account: &Address) -> bool {
+        let key = DataKey::Role(role, account.clone(), None);
+        env.storage().persistent().has(&key)
+    }
+
     /// Stores an asset
     ///
     /// # Arguments
@@ -63,72 +122,146 @@ impl GameAssetStorage {
         env.storage().persistent().has(&key)
     }
 
-    /// Gets all assets owned by a user
+    /// Deletes an asset
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    pub fn remove_asset(env: &Env, asset_id: &BytesN<32>) {
+        let key = DataKey::Asset(asset_id.clone());
+        env.storage().persistent().remove(&key);
+    }
+
+    /// Records that an asset was burned so its ID is never reused
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    /// * `record` - Final ownership record of the asset
+    pub fn set_burned(env: &Env, asset_id: &BytesN<32>, record: &OwnershipRecord) {
+        let key = DataKey::BurnedAsset(asset_id.clone());
+        env.storage().persistent().set(&key, record);
+    }
+
+    /// Checks if an asset ID belonged to a burned asset
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    ///
+    /// # Returns
+    /// * `bool` - True if the asset was burned, false otherwise
+    pub fn is_burned(env: &Env, asset_id: &BytesN<32>) -> bool {
+        let key = DataKey::BurnedAsset(asset_id.clone());
+        env.storage().persistent().has(&key)
+    }
+
+    /// Gets the number of assets owned by a user
     ///
     /// # Arguments
     /// * `env` - Reference to the contract environment
     /// * `owner` - Address of the owner
     ///
     /// # Returns
-    /// * `Vec<BytesN<32>>` - Vector of asset IDs owned by the user
-    pub fn get_user_assets(env: &Env, owner: &Address) -> Vec<BytesN<32>> {
-        let key = DataKey::UserAssets(owner.clone());
-        env.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env))
+    /// * `u32` - Number of assets owned by the user
+    pub fn get_user_asset_count(env: &Env, owner: &Address) -> u32 {
+        Self::fold_legacy_user_assets(env, owner);
+
+        let key = DataKey::UserAssetCount(owner.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
     }
 
-    /// Sets the assets owned by a user
+    /// Gets the asset at a position in a user's owned assets
     ///
     /// # Arguments
     /// * `env` - Reference to the contract environment
     /// * `owner` - Address of the owner
-    /// * `asset_ids` - Vector of asset IDs owned by the user
-    pub fn set_user_assets(env: &Env, owner: &Address, asset_ids: &Vec<BytesN<32>>) {
-        let key = DataKey::UserAssets(owner.clone());
-        env.storage().persistent().set(&key, asset_ids);
+    /// * `index` - Position in the owner's assets
+    ///
+    /// # Returns
+    /// * `Option<BytesN<32>>` - Asset ID at that position, if any
+    pub fn get_user_asset_at(env: &Env, owner: &Address, index: u32) -> Option<BytesN<32>> {
+        let key = DataKey::UserAssetAt(owner.clone(), index);
+        env.storage().persistent().get(&key)
     }
 
     /// Adds an asset to a user's owned assets
     ///
+    /// Each asset is its own entry, so the cost doesn't grow with the number of
+    /// assets the owner already holds
+    ///
     /// # Arguments
     /// * `env` - Reference to the contract environment
     /// * `owner` - Address of the owner
     /// * `asset_id` - Identifier of the asset to add
     pub fn add_user_asset(env: &Env, owner: &Address, asset_id: &BytesN<32>) {
-        let mut assets = Self::get_user_assets(env, owner);
+        let count = Self::get_user_asset_count(env, owner);
 
         // Only add if not already present
-        for existing_id in assets.iter() {
-            if &existing_id == asset_id {
-                return;
-            }
+        let slot_key = DataKey::UserAssetSlot(owner.clone(), asset_id.clone());
+        if env.storage().persistent().has(&slot_key) {
+            return;
         }
 
-        assets.push_back(asset_id.clone());
-        Self::set_user_assets(env, owner, &assets);
+        let at_key = DataKey::UserAssetAt(owner.clone(), count);
+        env.storage().persistent().set(&at_key, asset_id);
+        env.storage().persistent().set(&slot_key, &count);
+
+        let count_key = DataKey::UserAssetCount(owner.clone());
+        env.storage().persistent().set(&count_key, &(count + 1));
     }
 
     /// Removes an asset from a user's owned assets
     ///
+    /// The owner's last asset moves into the freed position, so positions stay contiguous
+    ///
     /// # Arguments
     /// * `env` - Reference to the contract environment
     /// * `owner` - Address of the owner
     /// * `asset_id` - Identifier of the asset to remove
     pub fn remove_user_asset(env: &Env, owner: &Address, asset_id: &BytesN<32>) {
-        let mut assets = Self::get_user_assets(env, owner);
-        let mut index_to_remove = None;
+        let count = Self::get_user_asset_count(env, owner);
 
-        for (i, existing_id) in assets.iter().enumerate() {
-            if &existing_id == asset_id {
-                index_to_remove = Some(i);
-                break;
+        let slot_key = DataKey::UserAssetSlot(owner.clone(), asset_id.clone());
+        let slot: u32 = match env.storage().persistent().get(&slot_key) {
+            Some(x) => x,
+            None => return,
+        };
+
+        let last = count - 1;
+        if slot != last {
+            if let Some(moved_id) = Self::get_user_asset_at(env, owner, last) {
+                let moved_key = DataKey::UserAssetAt(owner.clone(), slot);
+                env.storage().persistent().set(&moved_key, &moved_id);
+                let moved_slot_key = DataKey::UserAssetSlot(owner.clone(), moved_id);
+                env.storage().persistent().set(&moved_slot_key, &slot);
             }
         }
-        if let Some(index) = index_to_remove {
-            assets.remove(index as u32);
-            Self::set_user_assets(env, owner, &assets);
+
+        let last_key = DataKey::UserAssetAt(owner.clone(), last);
+        env.storage().persistent().remove(&last_key);
+        env.storage().persistent().remove(&slot_key);
+
+        let count_key = DataKey::UserAssetCount(owner.clone());
+        env.storage().persistent().set(&count_key, &last);
+    }
+
+    /// Moves the single list of owned assets older versions kept into one entry
+    /// per asset, the first time the owner's assets are read or changed
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `owner` - Address of the owner
+    fn fold_legacy_user_assets(env: &Env, owner: &Address) {
+        let legacy_key = DataKey::UserAssets(owner.clone());
+        let legacy: Vec<BytesN<32>> = match env.storage().persistent().get(&legacy_key) {
+            Some(x) => x,
+            None => return,
+        };
+        env.storage().persistent().remove(&legacy_key);
+
+        for asset_id in legacy.iter() {
+            Self::add_user_asset(env, owner, &asset_id);
         }
     }
 
@@ -176,6 +309,20 @@ impl GameAssetStorage {
         Self::set_all_asset_ids(env, &asset_ids);
     }
 
+    /// Removes an asset ID from the list of all registered assets
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset to remove
+    pub fn remove_asset_id(env: &Env, asset_id: &BytesN<32>) {
+        let mut asset_ids = Self::get_all_asset_ids(env);
+
+        if let Some(index) = asset_ids.first_index_of(asset_id) {
+            asset_ids.remove(index);
+            Self::set_all_asset_ids(env, &asset_ids);
+        }
+    }
+
     /// Gets the total count of all registered assets
     ///
     /// # Arguments
@@ -206,4 +353,288 @@ impl GameAssetStorage {
         let count = Self::get_asset_count(env) + 1;
         Self::set_asset_count(env, count);
     }
+
+    /// Decrements the asset count by 1
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    pub fn decrement_asset_count(env: &Env) {
+        let count = Self::get_asset_count(env).saturating_sub(1);
+        Self::set_asset_count(env, count);
+    }
+
+    /// Gets the approved spender of an asset
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    ///
+    /// # Returns
+    /// * `Option<AssetApproval>` - The approval, if one is set and not yet expired
+    pub fn get_asset_approval(env: &Env, asset_id: &BytesN<32>) -> Option<AssetApproval> {
+        let key = DataKey::AssetApproval(asset_id.clone());
+        let approval: Option<AssetApproval> = env.storage().temporary().get(&key);
+
+        approval.filter(|x| x.expiration_ledger >= env.ledger().sequence())
+    }
+
+    /// Sets the approved spender of an asset, kept in temporary storage until it expires
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    /// * `approval` - Spender and expiration ledger
+    pub fn set_asset_approval(env: &Env, asset_id: &BytesN<32>, approval: &AssetApproval) {
+        let key = DataKey::AssetApproval(asset_id.clone());
+        env.storage().temporary().set(&key, approval);
+
+        let live_for = approval
+            .expiration_ledger
+            .saturating_sub(env.ledger().sequence());
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, live_for, live_for);
+    }
+
+    /// Clears the approved spender of an asset
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `asset_id` - Identifier of the asset
+    pub fn remove_asset_approval(env: &Env, asset_id: &BytesN<32>) {
+        let key = DataKey::AssetApproval(asset_id.clone());
+        env.storage().temporary().remove(&key);
+    }
+
+    /// Checks whether an operator may transfer all of an owner's assets
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `owner` - Address of the owner
+    /// * `operator` - Address of the operator
+    ///
+    /// # Returns
+    /// * `bool` - True if the operator is approved, false otherwise
+    pub fn is_operator(env: &Env, owner: &Address, operator: &Address) -> bool {
+        let key = DataKey::OperatorApproval(owner.clone(), operator.clone());
+        env.storage().persistent().has(&key)
+    }
+
+    /// Grants or revokes an operator's approval over all of an owner's assets
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `owner` - Address of the owner
+    /// * `operator` - Address of the operator
+    /// * `approved` - Whether the operator is approved
+    pub fn set_operator(env: &Env, owner: &Address, operator: &Address, approved: bool) {
+        let key = DataKey::OperatorApproval(owner.clone(), operator.clone());
+        if approved {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Stores an edition
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition` - Edition to store
+    pub fn set_edition(env: &Env, edition: &Edition) {
+        let key = DataKey::Edition(edition.id.clone());
+        env.storage().persistent().set(&key, edition);
+    }
+
+    /// Retrieves an edition by ID
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    ///
+    /// # Returns
+    /// * `Option<Edition>` - The edition, if it exists
+    pub fn get_edition(env: &Env, edition_id: &BytesN<32>) -> Option<Edition> {
+        let key = DataKey::Edition(edition_id.clone());
+        env.storage().persistent().get(&key)
+    }
+
+    /// Gets the number of copies of an edition a holder owns
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `owner` - Address of the holder
+    ///
+    /// # Returns
+    /// * `u32` - Number of copies, zero if none
+    pub fn get_edition_balance(env: &Env, edition_id: &BytesN<32>, owner: &Address) -> u32 {
+        let key = DataKey::EditionBalance(edition_id.clone(), owner.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    /// Sets the number of copies of an edition a holder owns, dropping the entry at zero
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `owner` - Address of the holder
+    /// * `quantity` - New number of copies
+    pub fn set_edition_balance(env: &Env, edition_id: &BytesN<32>, owner: &Address, quantity: u32) {
+        let key = DataKey::EditionBalance(edition_id.clone(), owner.clone());
+        if quantity == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &quantity);
+        }
+    }
+
+    /// Stores one transfer record of an edition
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `index` - Position of the record in the edition's history
+    /// * `record` - Transfer record to store
+    pub fn set_edition_transfer(
+        env: &Env,
+        edition_id: &BytesN<32>,
+        index: u32,
+        record: &EditionTransferRecord,
+    ) {
+        let key = DataKey::EditionHistory(edition_id.clone(), index);
+        env.storage().persistent().set(&key, record);
+    }
+
+    /// Retrieves one transfer record of an edition
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `edition_id` - Identifier of the edition
+    /// * `index` - Position of the record in the edition's history
+    ///
+    /// # Returns
+    /// * `Option<EditionTransferRecord>` - The record, if it exists
+    pub fn get_edition_transfer(
+        env: &Env,
+        edition_id: &BytesN<32>,
+        index: u32,
+    ) -> Option<EditionTransferRecord> {
+        let key = DataKey::EditionHistory(edition_id.clone(), index);
+        env.storage().persistent().get(&key)
+    }
+
+    /// Stores a fungible resource
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource` - Resource to store
+    pub fn set_resource(env: &Env, resource: &ResourceInfo) {
+        let key = DataKey::Resource(resource.id.clone());
+        env.storage().persistent().set(&key, resource);
+    }
+
+    /// Retrieves a fungible resource by ID
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    ///
+    /// # Returns
+    /// * `Option<ResourceInfo>` - The resource, if it exists
+    pub fn get_resource(env: &Env, resource_id: &BytesN<32>) -> Option<ResourceInfo> {
+        let key = DataKey::Resource(resource_id.clone());
+        env.storage().persistent().get(&key)
+    }
+
+    /// Gets a holder's balance of a resource
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `holder` - Address of the holder
+    ///
+    /// # Returns
+    /// * `i128` - Balance, zero if the holder has none
+    pub fn get_resource_balance(env: &Env, resource_id: &BytesN<32>, holder: &Address) -> i128 {
+        let key = DataKey::ResourceBalance(resource_id.clone(), holder.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    /// Sets a holder's balance of a resource, dropping the entry when it reaches zero
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `holder` - Address of the holder
+    /// * `amount` - New balance
+    pub fn set_resource_balance(
+        env: &Env,
+        resource_id: &BytesN<32>,
+        holder: &Address,
+        amount: i128,
+    ) {
+        let key = DataKey::ResourceBalance(resource_id.clone(), holder.clone());
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &amount);
+        }
+    }
+
+    /// Gets a spender's allowance over a holder's resource balance
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the holder
+    /// * `spender` - Address of the spender
+    ///
+    /// # Returns
+    /// * `AllowanceValue` - The allowance, with a zero amount once expired
+    pub fn get_resource_allowance(
+        env: &Env,
+        resource_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+    ) -> AllowanceValue {
+        let key = DataKey::ResourceAllowance(resource_id.clone(), from.clone(), spender.clone());
+        let allowance: Option<AllowanceValue> = env.storage().temporary().get(&key);
+
+        match allowance {
+            Some(x) if x.expiration_ledger >= env.ledger().sequence() => x,
+            _ => AllowanceValue {
+                amount: 0,
+                expiration_ledger: 0,
+            },
+        }
+    }
+
+    /// Sets a spender's allowance, kept in temporary storage until it expires
+    ///
+    /// # Arguments
+    /// * `env` - Reference to the contract environment
+    /// * `resource_id` - Identifier of the resource
+    /// * `from` - Address of the holder
+    /// * `spender` - Address of the spender
+    /// * `allowance` - Amount and expiration ledger
+    pub fn set_resource_allowance(
+        env: &Env,
+        resource_id: &BytesN<32>,
+        from: &Address,
+        spender: &Address,
+        allowance: &AllowanceValue,
+    ) {
+        let key = DataKey::ResourceAllowance(resource_id.clone(), from.clone(), spender.clone());
+        env.storage().temporary().set(&key, allowance);
+
+        if allowance.amount > 0 {
+            let live_for = allowance
+                .expiration_ledger
+                .saturating_sub(env.ledger().sequence());
+            env.storage()
+                .temporary()
+                .extend_ttl(&key, live_for, live_for);
+        }
+    }
 }
@@ -1 +1,378 @@
+#![cfg(test)]
 
+use super::*;
+use crate::types::DataKey;
+use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, Env, Map, Vec};
+
+/// Register the registry with an admin that also holds an unscoped Minter role
+fn setup_test() -> (Env, GameAssetContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(GameAssetContract, ());
+    let client = GameAssetContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+
+    client.initialize(&admin);
+    client.grant_role(&Role::Minter, &admin, &None);
+
+    (env, client, admin)
+}
+
+fn asset_id(env: &Env, n: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[n; 32])
+}
+
+fn bytes(env: &Env, s: &str) -> Bytes {
+    Bytes::from_slice(env, s.as_bytes())
+}
+
+fn register(
+    env: &Env,
+    client: &GameAssetContractClient,
+    minter: &Address,
+    owner: &Address,
+    id: &BytesN<32>,
+) {
+    client.register_asset(
+        minter,
+        owner,
+        id,
+        &bytes(env, "Sword"),
+        &bytes(env, "weapon"),
+        &Map::new(env),
+    );
+}
+
+/// A classic account address; `Address::generate` gives contract addresses
+fn account_address(env: &Env) -> Address {
+    Address::from_str(
+        env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    )
+}
+
+#[test]
+fn test_register_and_transfer() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    register(&env, &client, &admin, &owner, &id);
+    assert_eq!(client.get_asset_count(), 1);
+    assert_eq!(client.get_asset_info(&id).owner, owner);
+
+    client.transfer_asset(&owner, &buyer, &id, &bytes(&env, "trade"));
+
+    assert_eq!(client.get_asset_info(&id).owner, buyer);
+    assert_eq!(client.get_asset_history(&id).len(), 1);
+    assert_eq!(client.get_user_assets(&owner).len(), 0);
+    assert_eq!(client.get_user_assets(&buyer).len(), 1);
+
+    // Only the current owner may transfer
+    let result = client.try_transfer_asset(&owner, &buyer, &id, &bytes(&env, "again"));
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+}
+
+#[test]
+fn test_user_assets_index_stays_dense() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    for n in 1..=3 {
+        register(&env, &client, &admin, &owner, &asset_id(&env, n));
+    }
+    client.transfer_asset(&owner, &buyer, &asset_id(&env, 1), &bytes(&env, "trade"));
+
+    // The last asset moves into the freed position
+    assert_eq!(client.get_user_asset_count(&owner), 2);
+    let page = client.get_user_assets_page(&owner, &0, &10);
+    assert_eq!(page.get(0).unwrap().id, asset_id(&env, 3));
+    assert_eq!(page.get(1).unwrap().id, asset_id(&env, 2));
+    assert_eq!(client.get_user_assets_page(&owner, &1, &10).len(), 1);
+    assert_eq!(client.get_user_assets(&buyer).len(), 1);
+}
+
+#[test]
+fn test_legacy_user_assets_are_folded_into_index() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    register(&env, &client, &admin, &owner, &asset_id(&env, 1));
+    register(&env, &client, &admin, &owner, &asset_id(&env, 2));
+
+    // Rewrite the owner's entries in the single-list layout of older versions
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        for n in 0..2 {
+            let id = asset_id(&env, n + 1);
+            storage.remove(&DataKey::UserAssetAt(owner.clone(), n as u32));
+            storage.remove(&DataKey::UserAssetSlot(owner.clone(), id));
+        }
+        storage.remove(&DataKey::UserAssetCount(owner.clone()));
+
+        let mut legacy = Vec::new(&env);
+        legacy.push_back(asset_id(&env, 1));
+        legacy.push_back(asset_id(&env, 2));
+        storage.set(&DataKey::UserAssets(owner.clone()), &legacy);
+    });
+
+    client.transfer_asset(&owner, &buyer, &asset_id(&env, 1), &bytes(&env, "trade"));
+
+    assert_eq!(client.get_user_asset_count(&owner), 1);
+    assert_eq!(
+        client.get_user_assets(&owner).get(0).unwrap().id,
+        asset_id(&env, 2)
+    );
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DataKey::UserAssets(owner.clone())));
+    });
+}
+
+#[test]
+fn test_resource_lifecycle() {
+    let (env, client, admin) = setup_test();
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let other = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    client.create_resource(&admin, &id, &bytes(&env, "Potion"), &0);
+    client.mint_resource(&admin, &id, &holder, &100);
+    client.transfer_resource(&id, &holder, &other, &30);
+
+    client.approve_resource(
+        &id,
+        &holder,
+        &spender,
+        &20,
+        &(env.ledger().sequence() + 100),
+    );
+    client.transfer_resource_from(&id, &spender, &holder, &other, &15);
+    assert_eq!(client.resource_allowance(&id, &holder, &spender), 5);
+
+    let result = client.try_transfer_resource_from(&id, &spender, &holder, &other, &10);
+    assert_eq!(result, Err(Ok(GameAssetError::InsufficientAllowance)));
+
+    client.burn_resource(&admin, &id, &other, &5);
+
+    assert_eq!(client.resource_balance(&id, &holder), 55);
+    assert_eq!(client.resource_balance(&id, &other), 40);
+    assert_eq!(client.get_resource(&id).total_supply, 95);
+}
+
+#[test]
+fn test_resource_needs_unscoped_minter() {
+    let (env, client, _) = setup_test();
+    let minter = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    client.grant_role(&Role::Minter, &minter, &Some(bytes(&env, "weapon")));
+    let result = client.try_create_resource(&minter, &id, &bytes(&env, "Potion"), &0);
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+}
+
+#[test]
+fn test_edition_mint_and_transfer() {
+    let (env, client, admin) = setup_test();
+    let holder = Address::generate(&env);
+    let other = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    client.create_edition(
+        &admin,
+        &id,
+        &bytes(&env, "Founder Cape"),
+        &bytes(&env, "cosmetic"),
+        &Map::new(&env),
+        &10,
+    );
+    client.mint_edition(&admin, &id, &holder, &8);
+
+    let result = client.try_mint_edition(&admin, &id, &holder, &3);
+    assert_eq!(result, Err(Ok(GameAssetError::EditionSupplyExceeded)));
+
+    client.transfer_edition(&holder, &other, &id, &3, &bytes(&env, "gift"));
+    client.transfer_edition(&other, &holder, &id, &1, &bytes(&env, "gift"));
+
+    assert_eq!(client.edition_balance(&id, &holder), 6);
+    assert_eq!(client.edition_balance(&id, &other), 2);
+
+    let edition = client.get_edition(&id);
+    assert_eq!(edition.minted, 8);
+    assert_eq!(edition.transfer_count, 2);
+
+    let page = client.get_edition_history(&id, &1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().from, other);
+}
+
+#[test]
+fn test_edition_minter_scoped_to_item_type() {
+    let (env, client, admin) = setup_test();
+    let minter = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    client.create_edition(
+        &admin,
+        &id,
+        &bytes(&env, "Founder Cape"),
+        &bytes(&env, "cosmetic"),
+        &Map::new(&env),
+        &10,
+    );
+
+    client.grant_role(&Role::Minter, &minter, &Some(bytes(&env, "weapon")));
+    let result = client.try_mint_edition(&minter, &id, &minter, &1);
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+
+    client.grant_role(&Role::Minter, &minter, &Some(bytes(&env, "cosmetic")));
+    client.mint_edition(&minter, &id, &minter, &1);
+    assert_eq!(client.edition_balance(&id, &minter), 1);
+}
+
+#[test]
+fn test_approval_and_operator_transfer() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    register(&env, &client, &admin, &owner, &id);
+
+    let result = client.try_transfer_from(&spender, &owner, &buyer, &id, &bytes(&env, "sale"));
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+
+    // A single-asset approval is cleared by the transfer it allows
+    client.approve(&owner, &spender, &id, &(env.ledger().sequence() + 100));
+    client.transfer_from(&spender, &owner, &buyer, &id, &bytes(&env, "sale"));
+    assert_eq!(client.get_asset_info(&id).owner, buyer);
+    assert_eq!(client.get_approved(&id), None);
+
+    // An operator may move any of the owner's assets
+    client.set_approval_for_all(&buyer, &spender, &true);
+    assert!(client.is_approved_for_all(&buyer, &spender));
+    client.transfer_from(&spender, &buyer, &owner, &id, &bytes(&env, "return"));
+    assert_eq!(client.get_asset_info(&id).owner, owner);
+}
+
+#[test]
+fn test_burn_reserves_id() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    register(&env, &client, &admin, &owner, &id);
+    client.burn_asset(&owner, &id, &bytes(&env, "consumed"));
+
+    assert!(!client.asset_exists(&id));
+    assert!(client.is_burned(&id));
+    assert_eq!(client.get_asset_count(), 0);
+
+    let result = client.try_register_asset(
+        &admin,
+        &owner,
+        &id,
+        &bytes(&env, "Sword"),
+        &bytes(&env, "weapon"),
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(GameAssetError::AssetBurned)));
+}
+
+#[test]
+fn test_admin_cannot_burn_contract_held_asset() {
+    let (env, client, admin) = setup_test();
+    let player = account_address(&env);
+    let escrow_contract = Address::generate(&env);
+
+    // The admin may burn an asset held by a player account
+    register(&env, &client, &admin, &player, &asset_id(&env, 1));
+    client.burn_asset(&admin, &asset_id(&env, 1), &bytes(&env, "moderation"));
+    assert!(client.is_burned(&asset_id(&env, 1)));
+
+    // but not one held in custody by a contract
+    register(&env, &client, &admin, &escrow_contract, &asset_id(&env, 2));
+    let result = client.try_burn_asset(&admin, &asset_id(&env, 2), &bytes(&env, "moderation"));
+    assert_eq!(result, Err(Ok(GameAssetError::OperationNotAllowed)));
+}
+
+#[test]
+fn test_is_contract_address_for_both_kinds() {
+    let env = Env::default();
+
+    assert!(is_contract_address(&env, &Address::generate(&env)));
+    assert!(!is_contract_address(&env, &account_address(&env)));
+}
+
+#[test]
+fn test_register_requires_minter_role() {
+    let (env, client, _) = setup_test();
+    let minter = Address::generate(&env);
+    let owner = Address::generate(&env);
+
+    let result = client.try_register_asset(
+        &minter,
+        &owner,
+        &asset_id(&env, 1),
+        &bytes(&env, "Sword"),
+        &bytes(&env, "weapon"),
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+
+    // A role scoped to one item type doesn't cover another
+    client.grant_role(&Role::Minter, &minter, &Some(bytes(&env, "armor")));
+    let result = client.try_register_asset(
+        &minter,
+        &owner,
+        &asset_id(&env, 1),
+        &bytes(&env, "Sword"),
+        &bytes(&env, "weapon"),
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+
+    client.grant_role(&Role::Minter, &minter, &Some(bytes(&env, "weapon")));
+    register(&env, &client, &minter, &owner, &asset_id(&env, 1));
+    assert!(client.asset_exists(&asset_id(&env, 1)));
+
+    client.revoke_role(&Role::Minter, &minter, &Some(bytes(&env, "weapon")));
+    assert!(!client.has_role(&Role::Minter, &minter, &bytes(&env, "weapon")));
+}
+
+#[test]
+fn test_update_metadata_with_editor_role() {
+    let (env, client, admin) = setup_test();
+    let owner = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let id = asset_id(&env, 1);
+
+    register(&env, &client, &admin, &owner, &id);
+
+    let result = client.try_update_metadata(
+        &editor,
+        &id,
+        &bytes(&env, "Sword +1"),
+        &bytes(&env, "weapon"),
+        &Map::new(&env),
+    );
+    assert_eq!(result, Err(Ok(GameAssetError::Unauthorized)));
+
+    client.grant_role(&Role::MetadataEditor, &editor, &Some(bytes(&env, "weapon")));
+    client.update_metadata(
+        &editor,
+        &id,
+        &bytes(&env, "Sword +1"),
+        &bytes(&env, "weapon"),
+        &Map::new(&env),
+    );
+    assert_eq!(client.get_asset_metadata(&id).name, bytes(&env, "Sword +1"));
+}
@@ -38,6 +38,82 @@ pub struct OwnershipRecord {
     pub reason: Bytes,
 }
 
+/// Roles the admin can delegate
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// May register new assets
+    Minter,
+    /// May update the metadata of existing assets
+    MetadataEditor,
+}
+
+/// Permission for one address to transfer a single asset, valid up to a ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetApproval {
+    /// Address allowed to transfer the asset
+    pub spender: Address,
+    /// Last ledger sequence the approval is valid for
+    pub expiration_ledger: u32,
+}
+
+/// A limited edition: one metadata template shared by up to `max_supply` copies
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edition {
+    /// Unique identifier for this edition
+    pub id: BytesN<32>,
+    /// Metadata shared by every copy
+    pub metadata: AssetMetadata,
+    /// Maximum number of copies that can ever be minted
+    pub max_supply: u32,
+    /// Number of copies minted so far
+    pub minted: u32,
+    /// Number of transfer records, each stored under its own `EditionHistory` key
+    pub transfer_count: u32,
+}
+
+/// Record of copies of an edition changing hands
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EditionTransferRecord {
+    /// Address the copies were sent from
+    pub from: Address,
+    /// Address the copies were sent to
+    pub to: Address,
+    /// Number of copies transferred
+    pub quantity: u32,
+    /// Timestamp of the transfer
+    pub timestamp: u64,
+    /// Reason for the transfer
+    pub reason: Bytes,
+}
+
+/// A fungible, stackable resource such as a potion, material or currency
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceInfo {
+    /// Unique identifier for this resource
+    pub id: BytesN<32>,
+    /// Name of the resource
+    pub name: Bytes,
+    /// Number of decimal places balances are expressed in
+    pub decimals: u32,
+    /// Total amount currently in circulation
+    pub total_supply: i128,
+}
+
+/// Amount a spender may move on an owner's behalf, valid up to a ledger
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    /// Remaining amount the spender may transfer
+    pub amount: i128,
+    /// Last ledger sequence the allowance is valid for
+    pub expiration_ledger: u32,
+}
+
 /// Storage keys for the contract
 #[contracttype]
 #[derive(Clone)]
@@ -46,10 +122,37 @@ pub enum DataKey {
     Admin,
     /// Key for storing assets by ID
     Asset(BytesN<32>),
-    /// Key for storing assets owned by a user
+    /// Legacy key for the assets owned by a user as a single list, folded into
+    /// the per-asset index below the first time the owner's assets are touched
     UserAssets(Address),
     /// Key for tracking all registered asset IDs
     AllAssetIds,
     /// Key for tracking total count of all assets
     AssetCount,
+    /// Key for a role held by an address, either for every item type or a single one
+    Role(Role, Address, Option<Bytes>),
+    /// Key for the final ownership record of a burned asset, reserving its ID
+    BurnedAsset(BytesN<32>),
+    /// Key for the approved spender of a single asset
+    AssetApproval(BytesN<32>),
+    /// Key for whether an operator may transfer all of an owner's assets
+    OperatorApproval(Address, Address),
+    /// Key for storing fungible resources by ID
+    Resource(BytesN<32>),
+    /// Key for a holder's balance of a resource
+    ResourceBalance(BytesN<32>, Address),
+    /// Key for a spender's allowance over a holder's resource balance
+    ResourceAllowance(BytesN<32>, Address, Address),
+    /// Key for storing editions by ID
+    Edition(BytesN<32>),
+    /// Key for the number of copies of an edition a holder owns
+    EditionBalance(BytesN<32>, Address),
+    /// Key for one transfer record of an edition, by position in its history
+    EditionHistory(BytesN<32>, u32),
+    /// Key for the number of assets owned by a user
+    UserAssetCount(Address),
+    /// Key for the asset at a position in a user's owned assets
+    UserAssetAt(Address, u32),
+    /// Key for the position of an asset in its owner's owned assets
+    UserAssetSlot(Address, BytesN<32>),
 }
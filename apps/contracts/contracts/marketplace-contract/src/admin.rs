@@ -0,0 +1,81 @@
+use soroban_sdk::{Address, BytesN, Env, Symbol};
+
+use crate::offers::internal_close_all_offers;
+use crate::storage::{
+    get_auction, get_dutch_auction, get_item_swap, get_rental, get_sealed_auction, get_trade_offer,
+    read, remove, write,
+};
+use crate::utils::{DataKey, Error};
+use crate::{auction, dutch_auction, rental, sealed_auction, swap, trade};
+
+pub fn is_paused(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&DataKey::Paused)
+        .unwrap_or(false)
+}
+
+pub fn set_paused(e: &Env, paused: bool) {
+    e.storage().instance().set(&DataKey::Paused, &paused);
+}
+
+pub fn is_blocked(e: &Env, user: &Address) -> bool {
+    read(e, &DataKey::Blocked(user.clone())).unwrap_or(false)
+}
+
+pub fn set_blocked(e: &Env, user: &Address, blocked: bool) {
+    let key = DataKey::Blocked(user.clone());
+    if blocked {
+        write(e, &key, &true);
+    } else {
+        remove(e, &key);
+    }
+}
+
+// New listings, bids and sales stop while paused; cancels and refunds keep working
+pub fn internal_check_not_paused(e: &Env) -> Result<(), Error> {
+    if is_paused(e) {
+        return Err(Error::ContractPaused);
+    }
+
+    Ok(())
+}
+
+pub fn internal_check_can_trade(e: &Env, user: &Address) -> Result<(), Error> {
+    internal_check_not_paused(e)?;
+
+    if is_blocked(e, user) {
+        return Err(Error::AddressBlocked);
+    }
+
+    Ok(())
+}
+
+// Pull an item off the market whatever it is listed as, refunding any escrowed bids and
+// offers. An item offered in a swap takes the whole swap down with it
+pub fn force_delist(e: &Env, item_id: &BytesN<32>) -> Result<(), Error> {
+    let seller = if let Some(offer) = get_trade_offer(e, item_id) {
+        trade::internal_force_delist(e, offer)?
+    } else if let Some(x) = get_auction(e, item_id).filter(|x| x.is_active) {
+        auction::internal_force_delist(e, x)?
+    } else if let Some(x) = get_dutch_auction(e, item_id).filter(|x| x.is_active) {
+        dutch_auction::internal_force_delist(e, x)?
+    } else if let Some(x) = get_sealed_auction(e, item_id).filter(|x| x.is_active) {
+        sealed_auction::internal_force_delist(e, x)?
+    } else if let Some(x) = get_rental(e, item_id) {
+        rental::internal_force_delist(e, x)?
+    } else if let Some(swap_id) = get_item_swap(e, item_id) {
+        swap::internal_force_delist(e, swap_id)?
+    } else {
+        return Err(Error::ItemNotListed);
+    };
+
+    internal_close_all_offers(e, item_id)?;
+
+    e.events().publish(
+        (Symbol::new(e, "item_force_delisted"), item_id.clone()),
+        seller,
+    );
+
+    Ok(())
+}
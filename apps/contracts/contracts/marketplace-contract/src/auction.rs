@@ -1,96 +1,91 @@
-use soroban_sdk::{Address, Env, Map, String, Symbol};
-
-use crate::utils::{Auction, DataKey, Error, GameItem};
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec};
+
+use crate::admin::{internal_check_can_trade, internal_check_not_paused};
+use crate::escrow::{deposit_escrow, internal_check_token, unlock_escrow, withdraw_escrow};
+use crate::fees::{pay_out_sale, MAX_BPS};
+use crate::history::record_trade;
+use crate::index::{
+    add_to_end_bucket, get_end_bucket, index_add, index_page, index_remove, last_end_bucket,
+    remove_from_end_bucket, END_BUCKET_SECONDS, MAX_PAGE_SIZE,
+};
+use crate::registry::{release_custody, take_custody};
+use crate::storage::{get_auction, is_listed, remove_auction, set_auction, set_listed};
+use crate::utils::{
+    Auction, AuctionConfig, AuctionPage, AuctionSort, BidIncrement, DataKey, Error, IndexKind,
+};
+
+// Hour buckets scanned per ending-soonest page
+const MAX_BUCKET_SCAN: u64 = 168;
 
 pub fn start_auction(
     e: Env,
-    item_id: String,
+    item_id: BytesN<32>,
     seller: Address,
     base_price: i128,
     duration: u64,
+    config: AuctionConfig,
+    token: Address,
 ) -> Result<(), Error> {
     seller.require_auth();
 
+    internal_check_can_trade(&e, &seller)?;
+
     if duration == 0 {
         return Err(Error::InvalidEndTime);
     }
 
-    let auction_key = DataKey::Auction;
-    let listed_key = DataKey::Listed;
-    let items_key = DataKey::Items;
-
-    let items: Map<String, GameItem> = match e.storage().instance().get(&items_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
-
-    let mut auctions: Map<String, Auction> = match e.storage().instance().get(&auction_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
-
-    let mut listed: Map<String, bool> = match e.storage().instance().get(&listed_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+    internal_check_token(&e, &token)?;
 
-    let item = match items.get(item_id.clone()) {
-        Some(x) => x,
-        None => return Err(Error::ItemNotFound),
-    };
+    internal_validate_config(base_price, &config)?;
 
-    if item.owner != seller {
-        return Err(Error::NotItemOwner);
+    if is_listed(&e, &item_id) {
+        return Err(Error::ItemAlreadyListed);
     }
 
-    if !listed.get(item_id.clone()).unwrap_or(false) {
-        return Err(Error::ItemNotListed);
-    }
-
-    if let Some(existing_auction) = auctions.get(item_id.clone()) {
+    if let Some(existing_auction) = get_auction(&e, &item_id) {
         if existing_auction.is_active {
-            return Err(Error::AlreadyInitialized);
+            return Err(Error::AuctionAlreadyExists);
         }
     }
 
+    // Hold the item in the marketplace until the auction is claimed or cancelled
+    take_custody(&e, &item_id, &seller)?;
+
     let auction_end_time = e.ledger().timestamp() + duration;
 
     let new_auction = Auction {
         item_id: item_id.clone(),
         seller: seller.clone(),
+        token,
         end_time: auction_end_time,
         highest_bid: base_price,
         highest_bidder: seller.clone(),
         is_active: true,
+        config,
     };
 
-    auctions.set(item_id.clone(), new_auction.clone());
-    e.storage().instance().set(&auction_key, &auctions);
+    set_auction(&e, &new_auction);
+    set_listed(&e, &item_id, true);
+    internal_index_auction(&e, &new_auction);
 
-    listed.set(item_id.clone(), true);
-    e.storage().instance().set(&listed_key, &listed);
-
-    e.events()
-        .publish((Symbol::new(&e, "Auction_started"), seller), new_auction);
+    e.events().publish(
+        (Symbol::new(&e, "Auction_started"), seller),
+        internal_public_view(new_auction),
+    );
 
     Ok(())
 }
 
-pub fn place_bid(e: Env, item_id: String, bidder: Address, bid: i128) -> Result<(), Error> {
+pub fn place_bid(e: Env, item_id: BytesN<32>, bidder: Address, bid: i128) -> Result<(), Error> {
     bidder.require_auth();
 
+    internal_check_can_trade(&e, &bidder)?;
+
     if bid <= 0 {
         return Err(Error::InvalidAmount);
     }
 
-    let auction_key = DataKey::Auction;
-
-    let mut auctions: Map<String, Auction> = match e.storage().instance().get(&auction_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
-
-    let mut existing_auction = match auctions.get(item_id.clone()) {
+    let mut existing_auction = match get_auction(&e, &item_id) {
         Some(x) => x,
         None => return Err(Error::AuctionNotFound),
     };
@@ -106,98 +101,266 @@ pub fn place_bid(e: Env, item_id: String, bidder: Address, bid: i128) -> Result<
     if existing_auction.end_time < current_time {
         return Err(Error::AuctionAlreadyEnded);
     }
-    if existing_auction.highest_bid >= bid {
+
+    // A bid at or above the buy-now price wins outright, capped at that price
+    let buy_now = match existing_auction.config.buy_now_price {
+        Some(price) => bid >= price,
+        None => false,
+    };
+    let bid = match existing_auction.config.buy_now_price {
+        Some(price) if buy_now => price,
+        _ => bid,
+    };
+
+    if !buy_now && bid < internal_min_next_bid(&existing_auction) {
         return Err(Error::BidTooLow);
     }
 
     let pre_bid = existing_auction.highest_bid;
-    let pre_bidder = existing_auction.highest_bidder;
+    let pre_bidder = existing_auction.highest_bidder.clone();
+
+    // Lock the new bid in escrow
+    deposit_escrow(&e, bidder.clone(), existing_auction.token.clone(), bid)?;
+
+    // Credit the outbid bidder's available balance for them to withdraw; the
+    // seller placeholder has nothing locked
+    if pre_bidder != existing_auction.seller {
+        unlock_escrow(
+            &e,
+            pre_bidder.clone(),
+            existing_auction.token.clone(),
+            pre_bid,
+        )?;
+
+        e.events().publish(
+            (Symbol::new(&e, "bid_refunded"), item_id.clone()),
+            (pre_bidder.clone(), pre_bid),
+        );
+    }
 
     existing_auction.highest_bid = bid;
     existing_auction.highest_bidder = bidder.clone();
 
-    auctions.set(item_id.clone(), existing_auction);
+    // Push the end out when a bid lands inside the extension window
+    let extension_window = internal_extension_window(&e, &existing_auction);
+    let pre_end_time = existing_auction.end_time;
+    if !buy_now && extension_window > 0 && pre_end_time - current_time < extension_window {
+        existing_auction.end_time = current_time + extension_window;
 
-    e.storage().instance().set(&auction_key, &auctions);
+        remove_from_end_bucket(&e, &item_id, pre_end_time);
+        add_to_end_bucket(&e, &item_id, existing_auction.end_time);
+    }
+
+    set_auction(&e, &existing_auction);
 
     e.events().publish(
-        (Symbol::new(&e, "placed_new_bid"), item_id),
-        (pre_bid, bid, pre_bidder, bidder),
+        (Symbol::new(&e, "placed_new_bid"), item_id.clone()),
+        (
+            pre_bid,
+            bid,
+            pre_bidder,
+            bidder.clone(),
+            pre_end_time,
+            existing_auction.end_time,
+        ),
     );
 
+    if buy_now {
+        internal_settle_auction(&e, existing_auction)?;
+
+        e.events().publish(
+            (Symbol::new(&e, "Auction_bought_now"), item_id),
+            (bidder, bid),
+        );
+    }
+
     Ok(())
 }
 
-pub fn claim_bid(e: Env, item_id: String, claimer: Address) -> Result<(), Error> {
+pub fn claim_bid(e: Env, item_id: BytesN<32>, claimer: Address) -> Result<(), Error> {
     claimer.require_auth();
 
-    let auction_key = DataKey::Auction;
-    let items_key = DataKey::Items;
+    internal_check_not_paused(&e)?;
 
-    let mut auctions: Map<String, Auction> = match e.storage().instance().get(&auction_key) {
-        Some(x) => x,
-        None => return Err(Error::AuctionNotFound),
-    };
+    let auction = internal_get_ended_auction(&e, &item_id)?;
 
-    let mut items: Map<String, GameItem> = match e.storage().instance().get(&items_key) {
-        Some(x) => x,
-        None => return Err(Error::ItemNotFound),
-    };
+    if auction.highest_bidder != claimer {
+        return Err(Error::InvalidCaller);
+    }
+    if !internal_reserve_met(&auction) {
+        return Err(Error::ReserveNotMet);
+    }
+
+    internal_settle_auction(&e, auction)?;
+
+    // Emit claim event
+    e.events().publish(
+        (Symbol::new(&e, "Auction_claimed"), item_id.clone()),
+        claimer,
+    );
+
+    Ok(())
+}
+
+// Anyone may settle once the auction has ended, so an absent winner cannot lock the item
+pub fn settle_auction(e: Env, item_id: BytesN<32>) -> Result<(), Error> {
+    internal_check_not_paused(&e)?;
 
-    let mut auction = match auctions.get(item_id.clone()) {
+    let auction = internal_get_ended_auction(&e, &item_id)?;
+
+    internal_settle_auction(&e, auction)
+}
+
+fn internal_get_ended_auction(e: &Env, item_id: &BytesN<32>) -> Result<Auction, Error> {
+    let auction = match get_auction(e, item_id) {
         Some(x) => x,
         None => return Err(Error::AuctionNotFound),
     };
 
-    let current_time = e.ledger().timestamp();
-
     if !auction.is_active {
         return Err(Error::AuctionNotActive);
     }
-    if auction.end_time > current_time {
+    if auction.end_time > e.ledger().timestamp() {
         return Err(Error::AuctionStillRunning);
     }
-    if auction.highest_bidder != claimer {
-        return Err(Error::InvalidCaller);
+
+    Ok(auction)
+}
+
+fn internal_validate_config(base_price: i128, config: &AuctionConfig) -> Result<(), Error> {
+    if let Some(reserve_price) = config.reserve_price {
+        if reserve_price <= 0 {
+            return Err(Error::InvalidAmount);
+        }
     }
 
-    let mut item = match items.get(item_id.clone()) {
-        Some(x) => x,
-        None => return Err(Error::ItemNotFound),
+    if let Some(buy_now_price) = config.buy_now_price {
+        if buy_now_price <= base_price {
+            return Err(Error::InvalidAmount);
+        }
+        if let Some(reserve_price) = config.reserve_price {
+            if reserve_price > buy_now_price {
+                return Err(Error::InvalidAmount);
+            }
+        }
+    }
+
+    match config.min_increment {
+        BidIncrement::Absolute(amount) if amount <= 0 => Err(Error::InvalidAmount),
+        BidIncrement::BasisPoints(bps) if bps == 0 || bps > MAX_BPS => Err(Error::InvalidAmount),
+        _ => Ok(()),
+    }
+}
+
+// Lowest bid that beats the current one; the opening bid only has to exceed the base price
+fn internal_min_next_bid(auction: &Auction) -> i128 {
+    let current = auction.highest_bid;
+
+    if auction.highest_bidder == auction.seller {
+        return current + 1;
+    }
+
+    let increment = match auction.config.min_increment {
+        BidIncrement::AnyHigher => 0,
+        BidIncrement::Absolute(amount) => amount,
+        BidIncrement::BasisPoints(bps) => current * bps as i128 / MAX_BPS as i128,
     };
 
-    // Transfer ownership
-    item.owner = claimer.clone();
+    current + increment.max(1)
+}
+
+fn internal_extension_window(e: &Env, auction: &Auction) -> u64 {
+    if auction.config.extension_window > 0 {
+        return auction.config.extension_window;
+    }
+
+    e.storage()
+        .instance()
+        .get(&DataKey::AuctionExtension)
+        .unwrap_or(0)
+}
+
+fn internal_reserve_met(auction: &Auction) -> bool {
+    match auction.config.reserve_price {
+        Some(reserve_price) => auction.highest_bid >= reserve_price,
+        None => true,
+    }
+}
+
+// Pay the seller, hand the item to the winner (or back to the seller if nobody bid
+// or the reserve wasn't met) and close the auction
+fn internal_settle_auction(e: &Env, mut auction: Auction) -> Result<(), Error> {
+    let item_id = auction.item_id.clone();
+
+    let has_bids = auction.highest_bidder != auction.seller;
+    let sold = has_bids && internal_reserve_met(&auction);
+
+    let split = if sold {
+        // Pay the seller from the winner's locked bid
+        let split = pay_out_sale(
+            e,
+            &item_id,
+            auction.highest_bidder.clone(),
+            auction.seller.clone(),
+            auction.token.clone(),
+            auction.highest_bid,
+        )?;
+
+        release_custody(e, &item_id, &auction.highest_bidder, b"marketplace_auction")?;
+
+        Some(split)
+    } else {
+        // Refund the top bidder if the reserve kept the item from selling
+        if has_bids {
+            withdraw_escrow(
+                e,
+                auction.highest_bidder.clone(),
+                auction.token.clone(),
+                auction.highest_bid,
+            )?;
+        }
+
+        release_custody(e, &item_id, &auction.seller, b"marketplace_auction_unsold")?;
+
+        None
+    };
 
-    // Update storage
-    items.set(item_id.clone(), item);
-    e.storage().instance().set(&items_key, &items);
+    set_listed(e, &item_id, false);
+    internal_unindex_auction(e, &auction);
 
     // Mark auction inactive
     auction.is_active = false;
-    auctions.set(item_id.clone(), auction);
-    e.storage().instance().set(&auction_key, &auctions);
+    set_auction(e, &auction);
+
+    if let Some(split) = split {
+        record_trade(
+            e,
+            item_id.clone(),
+            auction.seller.clone(),
+            auction.highest_bidder.clone(),
+            auction.token.clone(),
+            auction.highest_bid,
+            &split,
+        )?;
+    }
 
-    // Emit claim event
     e.events().publish(
-        (Symbol::new(&e, "Auction_claimed"), item_id.clone()),
-        claimer,
+        (Symbol::new(e, "Auction_settled"), item_id),
+        (
+            auction.seller,
+            auction.highest_bidder,
+            auction.highest_bid,
+            sold,
+        ),
     );
 
     Ok(())
 }
 
-pub fn cancel_auction(e: Env, item_id: String, seller: Address) -> Result<(), Error> {
+pub fn cancel_auction(e: Env, item_id: BytesN<32>, seller: Address) -> Result<(), Error> {
     seller.require_auth();
 
-    let auction_key = DataKey::Auction;
-
-    let mut auctions: Map<String, Auction> = match e.storage().instance().get(&auction_key) {
-        Some(x) => x,
-        None => return Err(Error::AuctionNotFound),
-    };
-
-    let auction = match auctions.get(item_id.clone()) {
+    let auction = match get_auction(&e, &item_id) {
         Some(x) => x,
         None => return Err(Error::AuctionNotFound),
     };
@@ -213,8 +376,7 @@ pub fn cancel_auction(e: Env, item_id: String, seller: Address) -> Result<(), Er
         return Err(Error::BidAlreadyPlaced);
     }
 
-    auctions.remove(item_id.clone());
-    e.storage().instance().set(&auction_key, &auctions);
+    internal_close_auction(&e, &auction, b"marketplace_auction_cancelled")?;
 
     // Emit
     e.events().publish(
@@ -224,3 +386,151 @@ pub fn cancel_auction(e: Env, item_id: String, seller: Address) -> Result<(), Er
 
     Ok(())
 }
+
+// Refund the standing bid, if any, and return the item to the seller
+pub fn internal_force_delist(e: &Env, auction: Auction) -> Result<Address, Error> {
+    if auction.highest_bidder != auction.seller {
+        withdraw_escrow(
+            e,
+            auction.highest_bidder.clone(),
+            auction.token.clone(),
+            auction.highest_bid,
+        )?;
+
+        e.events().publish(
+            (Symbol::new(e, "bid_refunded"), auction.item_id.clone()),
+            (auction.highest_bidder.clone(), auction.highest_bid),
+        );
+    }
+
+    internal_close_auction(e, &auction, b"marketplace_force_delisted")?;
+
+    Ok(auction.seller)
+}
+
+pub fn get_auction_by_id(e: Env, item_id: BytesN<32>) -> Result<Auction, Error> {
+    match get_auction(&e, &item_id) {
+        Some(x) => Ok(internal_public_view(x)),
+        None => Err(Error::AuctionNotFound),
+    }
+}
+
+// Page through running English auctions, oldest first or ending soonest first.
+// For `Created` the cursor is an index slot; for `EndTime` it is a timestamp.
+// Dutch and sealed-bid auctions aren't indexed; look them up by item id
+pub fn get_active_auctions(e: Env, sort: AuctionSort, cursor: u64, limit: u32) -> AuctionPage {
+    match sort {
+        AuctionSort::Created => internal_page_by_creation(&e, cursor, limit),
+        AuctionSort::EndTime => internal_page_by_end_time(&e, cursor, limit),
+    }
+}
+
+fn internal_index_auction(e: &Env, auction: &Auction) {
+    index_add(e, &IndexKind::Auctions, &auction.item_id);
+    add_to_end_bucket(e, &auction.item_id, auction.end_time);
+}
+
+fn internal_unindex_auction(e: &Env, auction: &Auction) {
+    index_remove(e, &IndexKind::Auctions, &auction.item_id);
+    remove_from_end_bucket(e, &auction.item_id, auction.end_time);
+}
+
+// Hand the item back to the seller and drop the auction
+fn internal_close_auction(e: &Env, auction: &Auction, reason: &[u8]) -> Result<(), Error> {
+    release_custody(e, &auction.item_id, &auction.seller, reason)?;
+
+    remove_auction(e, &auction.item_id);
+    set_listed(e, &auction.item_id, false);
+    internal_unindex_auction(e, auction);
+
+    Ok(())
+}
+
+// Leave the reserve out of query results so casual bidders aren't anchored to it;
+// this is cosmetic, anyone reading ledger storage can still see it
+fn internal_public_view(mut auction: Auction) -> Auction {
+    auction.config.reserve_price = None;
+    auction
+}
+
+fn internal_page_by_creation(e: &Env, cursor: u64, limit: u32) -> AuctionPage {
+    let (item_ids, next_cursor) = index_page(e, &IndexKind::Auctions, cursor, limit);
+
+    let mut auctions = Vec::new(e);
+    for item_id in item_ids.iter() {
+        if let Some(auction) = get_auction(e, &item_id) {
+            auctions.push_back(internal_public_view(auction));
+        }
+    }
+
+    AuctionPage {
+        auctions,
+        next_cursor,
+    }
+}
+
+// A page may run past `limit` so auctions ending in the same second are never split.
+// Scanning starts no earlier than now, so a zero cursor doesn't walk empty past buckets
+// and auctions that have ended but aren't settled yet are skipped
+fn internal_page_by_end_time(e: &Env, cursor: u64, limit: u32) -> AuctionPage {
+    let cursor = cursor.max(e.ledger().timestamp());
+    let limit = limit.min(MAX_PAGE_SIZE);
+    let last_bucket = last_end_bucket(e);
+    let mut bucket = cursor / END_BUCKET_SECONDS;
+    let scan_end = last_bucket.min(bucket.saturating_add(MAX_BUCKET_SCAN));
+
+    let mut auctions: Vec<Auction> = Vec::new(e);
+
+    while bucket <= scan_end {
+        for auction in internal_sorted_bucket(e, bucket, cursor).iter() {
+            if auctions.len() >= limit {
+                let last_end_time = auctions.last().map(|x| x.end_time).unwrap_or(0);
+                if auction.end_time != last_end_time {
+                    return AuctionPage {
+                        auctions,
+                        next_cursor: Some(auction.end_time),
+                    };
+                }
+            }
+            auctions.push_back(internal_public_view(auction));
+        }
+        bucket += 1;
+    }
+
+    let next_cursor = if bucket <= last_bucket {
+        Some(bucket * END_BUCKET_SECONDS)
+    } else {
+        None
+    };
+
+    AuctionPage {
+        auctions,
+        next_cursor,
+    }
+}
+
+// Auctions in one hour bucket ending at or after `from`, ordered by end time
+fn internal_sorted_bucket(e: &Env, bucket: u64, from: u64) -> Vec<Auction> {
+    let mut sorted: Vec<Auction> = Vec::new(e);
+
+    for item_id in get_end_bucket(e, bucket).iter() {
+        let auction = match get_auction(e, &item_id) {
+            Some(x) => x,
+            None => continue,
+        };
+        if auction.end_time < from {
+            continue;
+        }
+
+        let mut position = sorted.len();
+        for (i, other) in sorted.iter().enumerate() {
+            if other.end_time > auction.end_time {
+                position = i as u32;
+                break;
+            }
+        }
+        sorted.insert(position, auction);
+    }
+
+    sorted
+}
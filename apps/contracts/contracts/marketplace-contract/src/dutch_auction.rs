@@ -0,0 +1,180 @@
+use soroban_sdk::{Address, BytesN, Env, Symbol};
+
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token};
+use crate::fees::pay_out_sale;
+use crate::history::record_trade;
+use crate::registry::{release_custody, take_custody};
+use crate::storage::{
+    get_dutch_auction, is_listed, remove_dutch_auction, set_dutch_auction, set_listed,
+};
+use crate::utils::{DutchAuction, Error};
+
+#[allow(clippy::too_many_arguments)]
+pub fn start_dutch_auction(
+    e: Env,
+    item_id: BytesN<32>,
+    seller: Address,
+    token: Address,
+    start_price: i128,
+    floor_price: i128,
+    decay_amount: i128,
+    decay_interval: u64,
+) -> Result<(), Error> {
+    seller.require_auth();
+
+    internal_check_can_trade(&e, &seller)?;
+
+    if floor_price <= 0 || start_price <= floor_price || decay_amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if decay_interval == 0 {
+        return Err(Error::InvalidEndTime);
+    }
+
+    internal_check_token(&e, &token)?;
+
+    if is_listed(&e, &item_id) {
+        return Err(Error::ItemAlreadyListed);
+    }
+
+    // Hold the item in the marketplace until it is bought or the auction is cancelled
+    take_custody(&e, &item_id, &seller)?;
+
+    let new_auction = DutchAuction {
+        item_id: item_id.clone(),
+        seller: seller.clone(),
+        token,
+        start_price,
+        floor_price,
+        decay_amount,
+        decay_interval,
+        start_time: e.ledger().timestamp(),
+        is_active: true,
+    };
+
+    set_dutch_auction(&e, &new_auction);
+    set_listed(&e, &item_id, true);
+
+    e.events().publish(
+        (Symbol::new(&e, "Dutch_auction_started"), seller),
+        new_auction,
+    );
+
+    Ok(())
+}
+
+pub fn get_dutch_price(e: Env, item_id: BytesN<32>) -> Result<i128, Error> {
+    let auction = internal_get_active_dutch_auction(&e, &item_id)?;
+
+    Ok(internal_current_price(&e, &auction))
+}
+
+pub fn buy_dutch_auction(e: Env, item_id: BytesN<32>, buyer: Address) -> Result<(), Error> {
+    buyer.require_auth();
+
+    internal_check_can_trade(&e, &buyer)?;
+
+    let auction = internal_get_active_dutch_auction(&e, &item_id)?;
+
+    if auction.seller == buyer {
+        return Err(Error::InvalidCaller);
+    }
+    internal_check_can_trade(&e, &auction.seller)?;
+
+    let price = internal_current_price(&e, &auction);
+
+    // Collect the payment and pass it straight through to the seller
+    deposit_escrow(&e, buyer.clone(), auction.token.clone(), price)?;
+    let split = pay_out_sale(
+        &e,
+        &item_id,
+        buyer.clone(),
+        auction.seller.clone(),
+        auction.token.clone(),
+        price,
+    )?;
+
+    release_custody(&e, &item_id, &buyer, b"marketplace_dutch_auction")?;
+
+    remove_dutch_auction(&e, &item_id);
+    set_listed(&e, &item_id, false);
+
+    e.events().publish(
+        (Symbol::new(&e, "Dutch_auction_bought"), item_id.clone()),
+        (buyer.clone(), price),
+    );
+
+    record_trade(
+        &e,
+        item_id,
+        auction.seller,
+        buyer,
+        auction.token,
+        price,
+        &split,
+    )
+}
+
+pub fn cancel_dutch_auction(e: Env, item_id: BytesN<32>, seller: Address) -> Result<(), Error> {
+    seller.require_auth();
+
+    let auction = internal_get_active_dutch_auction(&e, &item_id)?;
+
+    if auction.seller != seller {
+        return Err(Error::InvalidCaller);
+    }
+
+    internal_close_dutch_auction(&e, &auction, b"marketplace_dutch_auction_cancelled")?;
+
+    e.events().publish(
+        (Symbol::new(&e, "Dutch_auction_cancelled"), item_id),
+        seller,
+    );
+
+    Ok(())
+}
+
+pub fn internal_force_delist(e: &Env, auction: DutchAuction) -> Result<Address, Error> {
+    internal_close_dutch_auction(e, &auction, b"marketplace_force_delisted")?;
+
+    Ok(auction.seller)
+}
+
+// Return the item to the seller and drop the auction
+fn internal_close_dutch_auction(
+    e: &Env,
+    auction: &DutchAuction,
+    reason: &[u8],
+) -> Result<(), Error> {
+    release_custody(e, &auction.item_id, &auction.seller, reason)?;
+
+    remove_dutch_auction(e, &auction.item_id);
+    set_listed(e, &auction.item_id, false);
+
+    Ok(())
+}
+
+fn internal_get_active_dutch_auction(e: &Env, item_id: &BytesN<32>) -> Result<DutchAuction, Error> {
+    let auction = match get_dutch_auction(e, item_id) {
+        Some(x) => x,
+        None => return Err(Error::AuctionNotFound),
+    };
+
+    if !auction.is_active {
+        return Err(Error::AuctionNotActive);
+    }
+
+    Ok(auction)
+}
+
+fn internal_current_price(e: &Env, auction: &DutchAuction) -> i128 {
+    let elapsed = e.ledger().timestamp() - auction.start_time;
+    let steps = (elapsed / auction.decay_interval) as i128;
+
+    let decayed = auction
+        .start_price
+        .saturating_sub(auction.decay_amount.saturating_mul(steps));
+
+    decayed.max(auction.floor_price)
+}
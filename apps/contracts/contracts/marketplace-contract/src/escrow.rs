@@ -1,64 +1,140 @@
-use soroban_sdk::{token, Address, Env, Map};
-use crate::utils::{DataKey, Error, EscrowBalance};
+use crate::admin::internal_check_can_trade;
+use crate::storage::{self, get_available_balance, set_available_balance, set_escrow_balance};
+use crate::utils::{DataKey, Error, EscrowAccount};
+use soroban_sdk::{token, Address, Env, Map, Symbol};
 
-pub fn internal_get_token(e: &Env) -> Result<Address, Error> {
-    let token_key = DataKey::TokenAddress;
+pub fn internal_check_token(e: &Env, token: &Address) -> Result<(), Error> {
+    let allowed_tokens_key = DataKey::AllowedTokens;
 
-    match e.storage().instance().get(&token_key) {
-        Some(addr) => Ok(addr),
-        None => Err(Error::NotInitialized),
+    let allowed_tokens: Map<Address, bool> = e
+        .storage()
+        .instance()
+        .get(&allowed_tokens_key)
+        .unwrap_or(Map::new(e));
+
+    if !allowed_tokens.get(token.clone()).unwrap_or(false) {
+        return Err(Error::TokenNotAllowed);
     }
+
+    Ok(())
 }
 
-pub fn deposit_escrow(e: &Env, user: Address, amount: i128) -> Result<(), Error> {
-    let escrow_key = DataKey::Escrow;
+// Pre-fund the marketplace so later bids and purchases draw on this balance
+pub fn deposit(e: Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+    user.require_auth();
 
-    let mut escrows: Map<Address, EscrowBalance> = e
-        .storage()
-        .instance()
-        .get(&escrow_key)
-        .unwrap_or(Map::new(e));
+    internal_check_can_trade(&e, &user)?;
 
-    let mut user_escrow = escrows
-        .get(user.clone())
-        .unwrap_or(EscrowBalance { balance: 0 });
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    internal_check_token(&e, &token)?;
 
-    let token_address = internal_get_token(e)?;
-    let client = token::Client::new(e, &token_address);
+    let client = token::Client::new(&e, &token);
     client.transfer(&user, &e.current_contract_address(), &amount);
 
-    user_escrow.balance += amount;
-    escrows.set(user.clone(), user_escrow);
+    let available = get_available_balance(&e, &user, &token);
+    set_available_balance(&e, &user, &token, available + amount);
 
-    e.storage().instance().set(&escrow_key, &escrows);
+    e.events()
+        .publish((Symbol::new(&e, "escrow_deposited"), user), (token, amount));
 
     Ok(())
 }
 
-pub fn withdraw_escrow(e: &Env, user: Address, amount: i128) -> Result<(), Error> {
-    let escrow_key = DataKey::Escrow;
-
-    let mut escrows: Map<Address, EscrowBalance> = e
-        .storage()
-        .instance()
-        .get(&escrow_key)
-        .unwrap_or(Map::new(e));
+// Pull out funds that aren't backing a bid or offer; works while paused or blocked
+pub fn withdraw(e: Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+    user.require_auth();
 
-    let mut user_escrow = escrows
-        .get(user.clone())
-        .unwrap_or(EscrowBalance { balance: 0 });
+    if amount <= 0 {
+        return Err(Error::InvalidAmount);
+    }
 
-    if user_escrow.balance < amount {
+    let available = get_available_balance(&e, &user, &token);
+    if available < amount {
         return Err(Error::InsufficientFunds);
     }
 
-    user_escrow.balance -= amount;
-    escrows.set(user.clone(), user_escrow);
-    e.storage().instance().set(&escrow_key, &escrows);
+    set_available_balance(&e, &user, &token, available - amount);
 
-    let token_address = internal_get_token(e)?;
-    let client = token::Client::new(e, &token_address);
+    let client = token::Client::new(&e, &token);
     client.transfer(&e.current_contract_address(), &user, &amount);
 
+    e.events()
+        .publish((Symbol::new(&e, "escrow_withdrawn"), user), (token, amount));
+
+    Ok(())
+}
+
+pub fn get_escrow_balance(e: Env, user: Address, token: Address) -> EscrowAccount {
+    EscrowAccount {
+        available: get_available_balance(&e, &user, &token),
+        locked: storage::get_escrow_balance(&e, &user, &token),
+    }
+}
+
+// Lock `amount` for a bid or purchase, spending the user's available balance
+// before pulling the rest from their wallet
+pub fn deposit_escrow(e: &Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+    let balance = storage::get_escrow_balance(e, &user, &token);
+
+    let available = get_available_balance(e, &user, &token);
+    let from_available = available.min(amount);
+    if from_available > 0 {
+        set_available_balance(e, &user, &token, available - from_available);
+    }
+
+    let shortfall = amount - from_available;
+    if shortfall > 0 {
+        let client = token::Client::new(e, &token);
+        client.transfer(&user, &e.current_contract_address(), &shortfall);
+    }
+
+    set_escrow_balance(e, &user, &token, balance + amount);
+
+    Ok(())
+}
+
+// Move part of a user's locked balance back to their available balance; no
+// token transfer, so a frozen or deauthorized account can't block the caller
+pub fn unlock_escrow(e: &Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+    let balance = storage::get_escrow_balance(e, &user, &token);
+
+    if balance < amount {
+        return Err(Error::InsufficientFunds);
+    }
+
+    set_escrow_balance(e, &user, &token, balance - amount);
+
+    let available = get_available_balance(e, &user, &token);
+    set_available_balance(e, &user, &token, available + amount);
+
+    Ok(())
+}
+
+pub fn withdraw_escrow(e: &Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+    release_escrow(e, user.clone(), user, token, amount)
+}
+
+// Pay out part of `from`'s escrowed balance of `token` directly to `to`
+pub fn release_escrow(
+    e: &Env,
+    from: Address,
+    to: Address,
+    token: Address,
+    amount: i128,
+) -> Result<(), Error> {
+    let balance = storage::get_escrow_balance(e, &from, &token);
+
+    if balance < amount {
+        return Err(Error::InsufficientFunds);
+    }
+
+    set_escrow_balance(e, &from, &token, balance - amount);
+
+    let client = token::Client::new(e, &token);
+    client.transfer(&e.current_contract_address(), &to, &amount);
+
     Ok(())
 }
@@ -0,0 +1,90 @@
+use soroban_sdk::{Address, BytesN, Env};
+
+use crate::escrow::release_escrow;
+use crate::registry::get_asset;
+use crate::storage::{get_collection_royalty, get_item_royalty};
+use crate::utils::{DataKey, Error, Royalty};
+
+pub const MAX_BPS: u32 = 10_000;
+
+pub struct SaleSplit {
+    pub platform_fee: i128,
+    pub royalty: i128,
+    pub seller_amount: i128,
+}
+
+pub fn internal_get_platform_fee(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::PlatformFee)
+        .unwrap_or(0)
+}
+
+// The fee and a royalty together must leave the seller something. Setting a
+// royalty checks it against the current fee; the fee can be raised later, so
+// pay_out_sale checks the pair again on every sale
+pub fn internal_check_fee_total(fee_bps: u32, royalty_bps: u32) -> Result<(), Error> {
+    if fee_bps.saturating_add(royalty_bps) > MAX_BPS {
+        return Err(Error::FeeTooHigh);
+    }
+
+    Ok(())
+}
+
+pub fn internal_get_royalty(e: &Env, item_id: &BytesN<32>) -> Result<Option<Royalty>, Error> {
+    // An item-specific royalty overrides the one set for its collection
+    if let Some(royalty) = get_item_royalty(e, item_id) {
+        return Ok(Some(royalty));
+    }
+
+    let asset = get_asset(e, item_id)?;
+
+    Ok(get_collection_royalty(e, &asset.metadata.item_type))
+}
+
+// Pay a sale out of `payer`'s escrowed funds: platform fee to the treasury,
+// royalty to the creator and the remainder to the seller
+pub fn pay_out_sale(
+    e: &Env,
+    item_id: &BytesN<32>,
+    payer: Address,
+    seller: Address,
+    token: Address,
+    price: i128,
+) -> Result<SaleSplit, Error> {
+    let fee_bps = internal_get_platform_fee(e);
+
+    let royalty = internal_get_royalty(e, item_id)?;
+    let royalty_bps = match &royalty {
+        Some(x) => x.bps,
+        None => 0,
+    };
+    internal_check_fee_total(fee_bps, royalty_bps)?;
+
+    let platform_fee = price * fee_bps as i128 / MAX_BPS as i128;
+    let royalty_amount = price * royalty_bps as i128 / MAX_BPS as i128;
+    let seller_amount = price - platform_fee - royalty_amount;
+
+    if platform_fee > 0 {
+        let treasury: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::Treasury)
+            .ok_or(Error::NotInitialized)?;
+        release_escrow(e, payer.clone(), treasury, token.clone(), platform_fee)?;
+    }
+
+    if let Some(x) = royalty {
+        if royalty_amount > 0 {
+            release_escrow(e, payer.clone(), x.recipient, token.clone(), royalty_amount)?;
+        }
+    }
+
+    release_escrow(e, payer, seller, token, seller_amount)?;
+
+    Ok(SaleSplit {
+        platform_fee,
+        royalty: royalty_amount,
+        seller_amount,
+    })
+}
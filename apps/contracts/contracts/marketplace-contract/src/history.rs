@@ -1,19 +1,47 @@
-use soroban_sdk::{Env, Map, String, Vec};
-use crate::utils::{DataKey, Error, TradeHistory};
+use crate::fees::SaleSplit;
+use crate::stats::record_sale;
+use crate::storage::{self, push_trade_history};
+use crate::utils::{Error, TradeHistory};
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec};
 
-pub fn get_trade_history(e: Env, item_id: String) -> Result<Vec<TradeHistory>, Error> {
-    let trade_history_key = DataKey::TradeHistory;
-
-    let histories: Map<String, Vec<TradeHistory>> =
-        match e.storage().instance().get(&trade_history_key) {
-            Some(x) => x,
-            None => return Err(Error::NotInitialized),
-        };
+pub fn get_trade_history(e: Env, item_id: BytesN<32>) -> Result<Vec<TradeHistory>, Error> {
+    Ok(storage::get_trade_history(&e, &item_id))
+}
 
-    let item_histories = match histories.get(item_id) {
-        Some(x) => x,
-        None => Vec::new(&e),
+pub fn record_trade(
+    e: &Env,
+    item_id: BytesN<32>,
+    seller: Address,
+    buyer: Address,
+    token: Address,
+    price: i128,
+    split: &SaleSplit,
+) -> Result<(), Error> {
+    let history_entry = TradeHistory {
+        seller: seller.clone(),
+        buyer: buyer.clone(),
+        price,
+        platform_fee: split.platform_fee,
+        royalty: split.royalty,
+        seller_amount: split.seller_amount,
+        timestamp: e.ledger().timestamp(),
     };
 
-    Ok(item_histories)
+    push_trade_history(e, &item_id, history_entry);
+    record_sale(e, &item_id, &token, price)?;
+
+    e.events().publish(
+        (Symbol::new(e, "Trade_History_Updated"), item_id),
+        (
+            seller,
+            buyer,
+            price,
+            split.platform_fee,
+            split.royalty,
+            split.seller_amount,
+            e.ledger().timestamp(),
+        ),
+    );
+
+    Ok(())
 }
@@ -0,0 +1,101 @@
+use soroban_sdk::{BytesN, Env, Vec};
+
+use crate::storage::{read, remove, write};
+use crate::utils::{DataKey, IndexKind};
+
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+// Upper bound on slots visited per page, so a run of removed entries can't exhaust the budget
+const MAX_PAGE_SCAN: u64 = 200;
+
+pub const END_BUCKET_SECONDS: u64 = 3_600;
+
+// Append-only, creation-ordered index; removing an item leaves a gap that pages skip
+pub fn index_add(e: &Env, kind: &IndexKind, item_id: &BytesN<32>) {
+    let slot_key = DataKey::IndexSlot(kind.clone(), item_id.clone());
+    if e.storage().persistent().has(&slot_key) {
+        return;
+    }
+
+    let len_key = DataKey::IndexLen(kind.clone());
+    let len: u64 = read(e, &len_key).unwrap_or(0);
+
+    write(e, &DataKey::IndexAt(kind.clone(), len), item_id);
+    write(e, &slot_key, &len);
+    write(e, &len_key, &(len + 1));
+}
+
+pub fn index_remove(e: &Env, kind: &IndexKind, item_id: &BytesN<32>) {
+    let slot_key = DataKey::IndexSlot(kind.clone(), item_id.clone());
+
+    if let Some(slot) = read::<u64>(e, &slot_key) {
+        remove(e, &DataKey::IndexAt(kind.clone(), slot));
+        remove(e, &slot_key);
+    }
+}
+
+// Up to `limit` ids starting at slot `start`, plus the slot to resume from
+pub fn index_page(
+    e: &Env,
+    kind: &IndexKind,
+    start: u64,
+    limit: u32,
+) -> (Vec<BytesN<32>>, Option<u64>) {
+    let len: u64 = read(e, &DataKey::IndexLen(kind.clone())).unwrap_or(0);
+    let limit = limit.min(MAX_PAGE_SIZE);
+    let scan_end = len.min(start.saturating_add(MAX_PAGE_SCAN));
+
+    let mut items = Vec::new(e);
+    let mut cursor = start;
+
+    while cursor < scan_end && items.len() < limit {
+        if let Some(item_id) = read(e, &DataKey::IndexAt(kind.clone(), cursor)) {
+            items.push_back(item_id);
+        }
+        cursor += 1;
+    }
+
+    let next_cursor = if cursor < len { Some(cursor) } else { None };
+
+    (items, next_cursor)
+}
+
+// Auctions are also bucketed by the hour they end in, for ending-soonest paging
+pub fn add_to_end_bucket(e: &Env, item_id: &BytesN<32>, end_time: u64) {
+    let bucket = end_time / END_BUCKET_SECONDS;
+
+    let mut items = get_end_bucket(e, bucket);
+    items.push_back(item_id.clone());
+    write(e, &DataKey::AuctionsEnding(bucket), &items);
+
+    if bucket > last_end_bucket(e) {
+        e.storage().instance().set(&DataKey::LastEndBucket, &bucket);
+    }
+}
+
+pub fn remove_from_end_bucket(e: &Env, item_id: &BytesN<32>, end_time: u64) {
+    let bucket = end_time / END_BUCKET_SECONDS;
+    let key = DataKey::AuctionsEnding(bucket);
+
+    let mut items = get_end_bucket(e, bucket);
+    if let Some(position) = items.first_index_of(item_id) {
+        items.remove(position);
+    }
+
+    if items.is_empty() {
+        remove(e, &key);
+    } else {
+        write(e, &key, &items);
+    }
+}
+
+pub fn get_end_bucket(e: &Env, bucket: u64) -> Vec<BytesN<32>> {
+    read(e, &DataKey::AuctionsEnding(bucket)).unwrap_or(Vec::new(e))
+}
+
+pub fn last_end_bucket(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&DataKey::LastEndBucket)
+        .unwrap_or(0)
+}
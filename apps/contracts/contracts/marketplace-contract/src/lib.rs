@@ -1,26 +1,82 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
 
-pub use auction::{cancel_auction, claim_bid, place_bid, start_auction};
+use admin::{force_delist, is_blocked, is_paused, set_blocked, set_paused};
+pub use auction::{
+    cancel_auction, claim_bid, get_active_auctions, get_auction_by_id, place_bid, settle_auction,
+    start_auction,
+};
+pub use dutch_auction::{
+    buy_dutch_auction, cancel_dutch_auction, get_dutch_price, start_dutch_auction,
+};
+use escrow::internal_check_token;
+pub use escrow::{deposit, get_escrow_balance, withdraw};
+use fees::{internal_check_fee_total, internal_get_platform_fee, MAX_BPS};
 pub use history::get_trade_history;
-pub use trade::{cancel_trade_offer, create_trade_offer, execute_trade};
-use utils::DataKey;
-pub use utils::{Error, TradeHistory};
+pub use offers::{accept_offer, get_offers, make_offer, reject_offer, withdraw_offer};
+pub use orders::{
+    cancel_order, fill_order, get_order_signer, is_order_nonce_used, order_payload,
+    set_order_signer,
+};
+pub use registry::{AssetRegistry, AssetRegistryClient};
+pub use rental::{
+    cancel_rental, forfeit_rental_collateral, get_rental_by_id, get_renter, list_for_rent,
+    rent_item, return_rental,
+};
+pub use sealed_auction::{
+    claim_sealed_bid, commit_bid, finalize_sealed_auction, get_sealed_auction_phase, reveal_bid,
+    start_sealed_auction,
+};
+pub use stats::get_collection_stats;
+use storage::{extend_instance, migrate_legacy};
+pub use swap::{accept_swap, cancel_swap, create_swap, get_swap};
+pub use trade::{
+    batch_create_trade_offers, batch_execute_trade, cancel_trade_offer, create_trade_offer,
+    execute_trade, get_active_listings, get_listings_by_seller, get_trade_offer_by_id,
+    purge_expired,
+};
+pub use utils::{
+    Auction, AuctionConfig, AuctionPage, AuctionSort, BatchListing, BidIncrement, BuyOffer,
+    CollectionStats, Error, EscrowAccount, ListingPage, Order, Rental, RentalTerms,
+    SealedAuctionConfig, SealedPhase, SealedPricing, SwapOffer, TradeHistory, TradeOffer,
+};
+use utils::{DataKey, Royalty};
 
+mod admin;
 mod auction;
+mod dutch_auction;
 mod escrow;
+mod fees;
 mod history;
+mod index;
+mod offers;
+mod orders;
+mod registry;
+mod rental;
+mod sealed_auction;
+mod stats;
+mod storage;
+mod swap;
 mod trade;
 mod utils;
 
+#[cfg(test)]
+mod test;
+
 #[contract]
 pub struct ContractMarketplace;
 
 #[contractimpl]
 impl ContractMarketplace {
-    pub fn initialize(e: Env, admin: Address, token: Address) -> Result<(), Error> {
+    pub fn initialize(
+        e: Env,
+        admin: Address,
+        token: Address,
+        asset_registry: Address,
+    ) -> Result<(), Error> {
         let admin_key = DataKey::Admin;
-        let token_key = DataKey::TokenAddress;
+        let allowed_tokens_key = DataKey::AllowedTokens;
+        let registry_key = DataKey::AssetRegistry;
         if e.storage().instance().has(&admin_key) {
             return Err(Error::AlreadyInitialized);
         }
@@ -28,64 +84,221 @@ impl ContractMarketplace {
         admin.require_auth();
 
         e.storage().instance().set(&admin_key, &admin);
-        e.storage().instance().set(&token_key, &token);
+        let mut allowed_tokens: Map<Address, bool> = Map::new(&e);
+        allowed_tokens.set(token, true);
+        e.storage()
+            .instance()
+            .set(&allowed_tokens_key, &allowed_tokens);
+        e.storage().instance().set(&registry_key, &asset_registry);
+        extend_instance(&e);
 
         Ok(())
     }
-    // set nnew token address
-    pub fn set_token_address(e: Env, admin: Address, new_token: Address) -> Result<(), Error> {
+    // allow a new payment token for listings and auctions
+    pub fn add_payment_token(e: Env, admin: Address, token: Address) -> Result<(), Error> {
+        Self::set_payment_token_allowed(&e, admin, token, true)
+    }
+    // stop accepting a payment token for new listings; open listings keep settling in it
+    pub fn remove_payment_token(e: Env, admin: Address, token: Address) -> Result<(), Error> {
+        Self::set_payment_token_allowed(&e, admin, token, false)
+    }
+    pub fn is_payment_token_allowed(e: Env, token: Address) -> bool {
+        internal_check_token(&e, &token).is_ok()
+    }
+    // set the marketplace-wide anti-sniping window for auctions that don't set their own
+    pub fn set_auction_extension(e: Env, admin: Address, window: u64) -> Result<(), Error> {
         admin.require_auth();
-        let admin_key = DataKey::Admin;
-        let token_key = DataKey::TokenAddress;
-        let current_admin: Address = e
-            .storage()
+        internal_check_admin(&e, &admin)?;
+        e.storage()
             .instance()
-            .get(&admin_key)
-            .ok_or(Error::NotInitialized)?;
-        if current_admin != admin {
-            return Err(Error::InvalidCaller);
+            .set(&DataKey::AuctionExtension, &window);
+        e.events()
+            .publish((Symbol::new(&e, "auction_extension_set"), admin), window);
+
+        Ok(())
+    }
+    // set the platform fee (in basis points) and the treasury that receives it
+    pub fn set_platform_fee(
+        e: Env,
+        admin: Address,
+        fee_bps: u32,
+        treasury: Address,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        if fee_bps > MAX_BPS {
+            return Err(Error::FeeTooHigh);
         }
-        e.storage().instance().set(&token_key, &new_token);
+        e.storage().instance().set(&DataKey::PlatformFee, &fee_bps);
+        e.storage().instance().set(&DataKey::Treasury, &treasury);
+        e.events().publish(
+            (Symbol::new(&e, "platform_fee_set"), admin),
+            (fee_bps, treasury),
+        );
+
+        Ok(())
+    }
+    // set the creator royalty for a single item, overriding its collection's royalty
+    pub fn set_item_royalty(
+        e: Env,
+        admin: Address,
+        item_id: BytesN<32>,
+        recipient: Address,
+        bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        internal_check_fee_total(internal_get_platform_fee(&e), bps)?;
+        storage::set_item_royalty(&e, &item_id, &Royalty { recipient, bps });
+        e.events()
+            .publish((Symbol::new(&e, "item_royalty_set"), item_id), bps);
+
+        Ok(())
+    }
+    // set the creator royalty for every item of a registry `item_type`
+    pub fn set_collection_royalty(
+        e: Env,
+        admin: Address,
+        item_type: Bytes,
+        recipient: Address,
+        bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        internal_check_fee_total(internal_get_platform_fee(&e), bps)?;
+        storage::set_collection_royalty(&e, &item_type, &Royalty { recipient, bps });
+        e.events()
+            .publish((Symbol::new(&e, "collection_royalty_set"), item_type), bps);
+
+        Ok(())
+    }
+    // upgrade a deployment of the original contract: set the asset registry it never had
+    // and move its single payment token into the allowlist
+    pub fn migrate_storage(e: Env, admin: Address, asset_registry: Address) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        migrate_legacy(&e, &asset_registry)?;
         e.events()
-            .publish((Symbol::new(&e, "new_token_address_set"), admin), new_token);
+            .publish((Symbol::new(&e, "storage_migrated"), admin), asset_registry);
 
         Ok(())
     }
+    // halt new listings, bids and sales; cancels and refunds stay open
+    pub fn pause(e: Env, admin: Address) -> Result<(), Error> {
+        Self::set_pause(&e, admin, true)
+    }
+    pub fn unpause(e: Env, admin: Address) -> Result<(), Error> {
+        Self::set_pause(&e, admin, false)
+    }
+    pub fn is_paused(e: Env) -> bool {
+        is_paused(&e)
+    }
+    // stop an address from listing, bidding or buying; it can still cancel and withdraw
+    pub fn block_address(e: Env, admin: Address, user: Address) -> Result<(), Error> {
+        Self::set_address_blocked(&e, admin, user, true)
+    }
+    pub fn unblock_address(e: Env, admin: Address, user: Address) -> Result<(), Error> {
+        Self::set_address_blocked(&e, admin, user, false)
+    }
+    pub fn is_blocked(e: Env, user: Address) -> bool {
+        is_blocked(&e, &user)
+    }
+    // take an item off the market, returning it to the seller and refunding escrowed bids and offers
+    pub fn force_delist(e: Env, admin: Address, item_id: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        force_delist(&e, &item_id)
+    }
+    // Deposit tokens to fund later bids and purchases
+    pub fn deposit(e: Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+        deposit(e, user, token, amount)
+    }
+
+    // Withdraw tokens that aren't locked behind a bid or offer
+    pub fn withdraw(e: Env, user: Address, token: Address, amount: i128) -> Result<(), Error> {
+        withdraw(e, user, token, amount)
+    }
+
+    // Available and locked escrow of a user in one token
+    pub fn get_escrow_balance(e: Env, user: Address, token: Address) -> EscrowAccount {
+        get_escrow_balance(e, user, token)
+    }
+
     // List an item for sale
     pub fn list_for_sale(
         e: Env,
-        item_id: String,
+        item_id: BytesN<32>,
         seller: Address,
         price: i128,
+        token: Address,
+        expires_at: Option<u64>,
     ) -> Result<(), utils::Error> {
-        create_trade_offer(e, item_id, seller, price)
+        create_trade_offer(e, item_id, seller, price, token, expires_at)
+    }
+
+    // List several items in one call; all are listed or none are
+    pub fn batch_list_for_sale(
+        e: Env,
+        listings: Vec<BatchListing>,
+        seller: Address,
+        token: Address,
+        expires_at: Option<u64>,
+    ) -> Result<Vec<TradeOffer>, utils::Error> {
+        batch_create_trade_offers(e, listings, seller, token, expires_at)
     }
 
     // Delist an item
-    pub fn delist_from_sale(e: Env, item_id: String, seller: Address) -> Result<(), utils::Error> {
+    pub fn delist_from_sale(
+        e: Env,
+        item_id: BytesN<32>,
+        seller: Address,
+    ) -> Result<(), utils::Error> {
         cancel_trade_offer(e, item_id, seller)
     }
 
+    // Delist whichever of the given listings have expired; callable by anyone
+    pub fn purge_expired(
+        e: Env,
+        item_ids: Vec<BytesN<32>>,
+    ) -> Result<Vec<BytesN<32>>, utils::Error> {
+        purge_expired(e, item_ids)
+    }
+
     // Finalize a peer-to-peer trade
-    pub fn p2p_execute_trade(e: Env, item_id: String, buyer: Address) -> Result<(), utils::Error> {
+    pub fn p2p_execute_trade(
+        e: Env,
+        item_id: BytesN<32>,
+        buyer: Address,
+    ) -> Result<(), utils::Error> {
         execute_trade(e, item_id, buyer)
     }
 
+    // Buy several listings priced in the same token with one payment
+    pub fn batch_execute_trade(
+        e: Env,
+        item_ids: Vec<BytesN<32>>,
+        buyer: Address,
+    ) -> Result<(), utils::Error> {
+        batch_execute_trade(e, item_ids, buyer)
+    }
+
     // Start an auction
     pub fn start_auction(
         e: Env,
-        item_id: String,
+        item_id: BytesN<32>,
         seller: Address,
         starting_bid: i128,
         duration: u64,
+        config: AuctionConfig,
+        token: Address,
     ) -> Result<(), utils::Error> {
-        start_auction(e, item_id, seller, starting_bid, duration)
+        start_auction(e, item_id, seller, starting_bid, duration, config, token)
     }
 
     // Place a bid
     pub fn place_auction_bid(
         e: Env,
-        item_id: String,
+        item_id: BytesN<32>,
         bidder: Address,
         bid_amount: i128,
     ) -> Result<(), utils::Error> {
@@ -95,19 +308,394 @@ impl ContractMarketplace {
     // Claim the winning auction item
     pub fn claim_auction_bid(
         e: Env,
-        item_id: String,
+        item_id: BytesN<32>,
         claimer: Address,
     ) -> Result<(), utils::Error> {
         claim_bid(e, item_id, claimer)
     }
 
+    // Settle an ended auction on behalf of the winner or seller
+    pub fn settle_auction(e: Env, item_id: BytesN<32>) -> Result<(), utils::Error> {
+        settle_auction(e, item_id)
+    }
+
     // Cancel an auction
-    pub fn cancel_auction(e: Env, item_id: String, seller: Address) -> Result<(), utils::Error> {
+    pub fn cancel_auction(
+        e: Env,
+        item_id: BytesN<32>,
+        seller: Address,
+    ) -> Result<(), utils::Error> {
         cancel_auction(e, item_id, seller)
     }
 
+    // Start a descending-price auction
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_dutch_auction(
+        e: Env,
+        item_id: BytesN<32>,
+        seller: Address,
+        token: Address,
+        start_price: i128,
+        floor_price: i128,
+        decay_amount: i128,
+        decay_interval: u64,
+    ) -> Result<(), utils::Error> {
+        start_dutch_auction(
+            e,
+            item_id,
+            seller,
+            token,
+            start_price,
+            floor_price,
+            decay_amount,
+            decay_interval,
+        )
+    }
+
+    // Current asking price of a Dutch auction
+    pub fn get_dutch_price(e: Env, item_id: BytesN<32>) -> Result<i128, utils::Error> {
+        get_dutch_price(e, item_id)
+    }
+
+    // Buy a Dutch auction item at its current price
+    pub fn buy(e: Env, item_id: BytesN<32>, buyer: Address) -> Result<(), utils::Error> {
+        buy_dutch_auction(e, item_id, buyer)
+    }
+
+    // Cancel a Dutch auction
+    pub fn cancel_dutch_auction(
+        e: Env,
+        item_id: BytesN<32>,
+        seller: Address,
+    ) -> Result<(), utils::Error> {
+        cancel_dutch_auction(e, item_id, seller)
+    }
+
+    // Start a sealed-bid commit/reveal auction
+    pub fn start_sealed_auction(
+        e: Env,
+        item_id: BytesN<32>,
+        seller: Address,
+        config: SealedAuctionConfig,
+    ) -> Result<(), utils::Error> {
+        start_sealed_auction(e, item_id, seller, config)
+    }
+
+    // Commit to a sealed bid, locking the auction deposit
+    pub fn commit_sealed_bid(
+        e: Env,
+        item_id: BytesN<32>,
+        bidder: Address,
+        commitment: BytesN<32>,
+    ) -> Result<(), utils::Error> {
+        commit_bid(e, item_id, bidder, commitment)
+    }
+
+    // Reveal a previously committed sealed bid
+    pub fn reveal_sealed_bid(
+        e: Env,
+        item_id: BytesN<32>,
+        bidder: Address,
+        bid: i128,
+        salt: Bytes,
+    ) -> Result<(), utils::Error> {
+        reveal_bid(e, item_id, bidder, bid, salt)
+    }
+
+    // Pick the winner of a sealed-bid auction and hand over the item
+    pub fn finalize_sealed_auction(e: Env, item_id: BytesN<32>) -> Result<(), utils::Error> {
+        finalize_sealed_auction(e, item_id)
+    }
+
+    // Refund a losing bid, or forfeit an unrevealed deposit, once the auction is finalized
+    pub fn claim_sealed_bid(
+        e: Env,
+        item_id: BytesN<32>,
+        bidder: Address,
+    ) -> Result<(), utils::Error> {
+        claim_sealed_bid(e, item_id, bidder)
+    }
+
+    // Current phase of a sealed-bid auction
+    pub fn get_sealed_auction_phase(
+        e: Env,
+        item_id: BytesN<32>,
+    ) -> Result<SealedPhase, utils::Error> {
+        get_sealed_auction_phase(e, item_id)
+    }
+
+    // Register the ed25519 key used to sign your off-chain orders
+    pub fn set_order_signer(
+        e: Env,
+        seller: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), utils::Error> {
+        set_order_signer(e, seller, public_key)
+    }
+
+    // Key registered for a seller's off-chain orders
+    pub fn get_order_signer(e: Env, seller: Address) -> Option<BytesN<32>> {
+        get_order_signer(e, seller)
+    }
+
+    // Buy an item against a seller-signed off-chain order
+    pub fn fill_order(
+        e: Env,
+        order: Order,
+        signature: BytesN<64>,
+        buyer: Address,
+    ) -> Result<(), utils::Error> {
+        fill_order(e, order, signature, buyer)
+    }
+
+    // Invalidate every off-chain order signed with this nonce
+    pub fn cancel_order(e: Env, seller: Address, nonce: u64) -> Result<(), utils::Error> {
+        cancel_order(e, seller, nonce)
+    }
+
+    // Whether a nonce has been filled or cancelled
+    pub fn is_order_nonce_used(e: Env, seller: Address, nonce: u64) -> bool {
+        is_order_nonce_used(e, seller, nonce)
+    }
+
+    // Exact bytes a seller has to sign for an order
+    pub fn order_payload(e: Env, order: Order) -> Bytes {
+        order_payload(e, order)
+    }
+
+    // List an item for rent by the period, against collateral; the marketplace keeps
+    // custody and the renter gets usage rights, checked through get_renter
+    pub fn list_for_rent(
+        e: Env,
+        item_id: BytesN<32>,
+        owner: Address,
+        terms: RentalTerms,
+    ) -> Result<(), utils::Error> {
+        list_for_rent(e, item_id, owner, terms)
+    }
+
+    // Rent a listed item for a number of periods, paying rent and locking collateral
+    pub fn rent_item(
+        e: Env,
+        item_id: BytesN<32>,
+        renter: Address,
+        periods: u32,
+    ) -> Result<(), utils::Error> {
+        rent_item(e, item_id, renter, periods)
+    }
+
+    // End a rental early, or after it ran out, and get the collateral back
+    pub fn return_rental(e: Env, item_id: BytesN<32>, renter: Address) -> Result<(), utils::Error> {
+        return_rental(e, item_id, renter)
+    }
+
+    // Admin only: pay the collateral to the owner when a game reports the renter misused the item
+    pub fn forfeit_rental_collateral(
+        e: Env,
+        admin: Address,
+        item_id: BytesN<32>,
+    ) -> Result<(), utils::Error> {
+        admin.require_auth();
+        internal_check_admin(&e, &admin)?;
+        forfeit_rental_collateral(&e, &item_id)
+    }
+
+    // Take down a rental listing that isn't currently rented
+    pub fn cancel_rental(e: Env, item_id: BytesN<32>, owner: Address) -> Result<(), utils::Error> {
+        cancel_rental(e, item_id, owner)
+    }
+
+    // Rental listing for an item
+    pub fn get_rental(e: Env, item_id: BytesN<32>) -> Result<Rental, utils::Error> {
+        get_rental_by_id(e, item_id)
+    }
+
+    // Current renter of an item, if a rental is running
+    pub fn get_renter(e: Env, item_id: BytesN<32>) -> Option<Address> {
+        get_renter(e, item_id)
+    }
+
+    // Make a binding, escrowed offer on any item, listed or not
+    pub fn make_offer(
+        e: Env,
+        item_id: BytesN<32>,
+        buyer: Address,
+        price: i128,
+        token: Address,
+        expires_at: u64,
+    ) -> Result<(), utils::Error> {
+        make_offer(e, item_id, buyer, price, token, expires_at)
+    }
+
+    // Accept a buyer's offer on an item you own
+    pub fn accept_offer(
+        e: Env,
+        item_id: BytesN<32>,
+        owner: Address,
+        buyer: Address,
+    ) -> Result<(), utils::Error> {
+        accept_offer(e, item_id, owner, buyer)
+    }
+
+    // Reject a buyer's offer and refund them
+    pub fn reject_offer(
+        e: Env,
+        item_id: BytesN<32>,
+        owner: Address,
+        buyer: Address,
+    ) -> Result<(), utils::Error> {
+        reject_offer(e, item_id, owner, buyer)
+    }
+
+    // Withdraw your own unaccepted offer
+    pub fn withdraw_offer(e: Env, item_id: BytesN<32>, buyer: Address) -> Result<(), utils::Error> {
+        withdraw_offer(e, item_id, buyer)
+    }
+
+    // Open offers on an item
+    pub fn get_offers(e: Env, item_id: BytesN<32>) -> Vec<BuyOffer> {
+        get_offers(e, item_id)
+    }
+
+    // Offer a bundle of items and/or tokens in exchange for another party's bundle
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_swap(
+        e: Env,
+        maker: Address,
+        taker: Address,
+        maker_items: Vec<BytesN<32>>,
+        taker_items: Vec<BytesN<32>>,
+        token: Address,
+        maker_amount: i128,
+        taker_amount: i128,
+    ) -> Result<u64, utils::Error> {
+        create_swap(
+            e,
+            maker,
+            taker,
+            maker_items,
+            taker_items,
+            token,
+            maker_amount,
+            taker_amount,
+        )
+    }
+
+    // Accept a swap addressed to you, exchanging both bundles atomically
+    pub fn accept_swap(e: Env, swap_id: u64, taker: Address) -> Result<(), utils::Error> {
+        accept_swap(e, swap_id, taker)
+    }
+
+    // Cancel your swap and take back the offered bundle
+    pub fn cancel_swap(e: Env, swap_id: u64, maker: Address) -> Result<(), utils::Error> {
+        cancel_swap(e, swap_id, maker)
+    }
+
+    // Retrieve a pending swap
+    pub fn get_swap(e: Env, swap_id: u64) -> Result<SwapOffer, utils::Error> {
+        get_swap(e, swap_id)
+    }
+
+    // Retrieve a single listing
+    pub fn get_trade_offer(e: Env, item_id: BytesN<32>) -> Result<TradeOffer, utils::Error> {
+        get_trade_offer_by_id(e, item_id)
+    }
+
+    // Page through active listings, oldest first; pass back `next_cursor` for the next page
+    pub fn get_active_listings(e: Env, cursor: u64, limit: u32) -> ListingPage {
+        get_active_listings(e, cursor, limit)
+    }
+
+    // Page through a seller's active listings
+    pub fn get_listings_by_seller(e: Env, seller: Address, cursor: u64, limit: u32) -> ListingPage {
+        get_listings_by_seller(e, seller, cursor, limit)
+    }
+
+    // Retrieve a single auction, with its reserve price left out (it is still readable on-chain)
+    pub fn get_auction(e: Env, item_id: BytesN<32>) -> Result<Auction, utils::Error> {
+        get_auction_by_id(e, item_id)
+    }
+
+    // Page through running English auctions by creation or end time
+    pub fn get_active_auctions(e: Env, sort: AuctionSort, cursor: u64, limit: u32) -> AuctionPage {
+        get_active_auctions(e, sort, cursor, limit)
+    }
+
     // Retrieve trade history
-    pub fn get_trade_history(e: Env, item_id: String) -> Result<Vec<TradeHistory>, Error> {
+    pub fn get_trade_history(e: Env, item_id: BytesN<32>) -> Result<Vec<TradeHistory>, Error> {
         get_trade_history(e, item_id)
     }
+
+    // Last sale, floor and rolling volume for an item type, priced in one token
+    pub fn get_collection_stats(e: Env, item_type: Bytes, token: Address) -> CollectionStats {
+        get_collection_stats(e, item_type, token)
+    }
+
+    fn set_pause(e: &Env, admin: Address, paused: bool) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(e, &admin)?;
+        set_paused(e, paused);
+        e.events()
+            .publish((Symbol::new(e, "marketplace_paused"), admin), paused);
+
+        Ok(())
+    }
+
+    fn set_address_blocked(
+        e: &Env,
+        admin: Address,
+        user: Address,
+        blocked: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(e, &admin)?;
+        set_blocked(e, &user, blocked);
+        e.events()
+            .publish((Symbol::new(e, "address_blocked"), admin), (user, blocked));
+
+        Ok(())
+    }
+
+    fn set_payment_token_allowed(
+        e: &Env,
+        admin: Address,
+        token: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        internal_check_admin(e, &admin)?;
+        let allowed_tokens_key = DataKey::AllowedTokens;
+        let mut allowed_tokens: Map<Address, bool> = e
+            .storage()
+            .instance()
+            .get(&allowed_tokens_key)
+            .unwrap_or(Map::new(e));
+        if allowed {
+            allowed_tokens.set(token.clone(), true);
+        } else {
+            allowed_tokens.remove(token.clone());
+        }
+        e.storage()
+            .instance()
+            .set(&allowed_tokens_key, &allowed_tokens);
+        e.events().publish(
+            (Symbol::new(e, "payment_token_updated"), admin),
+            (token, allowed),
+        );
+
+        Ok(())
+    }
+}
+
+fn internal_check_admin(e: &Env, admin: &Address) -> Result<(), Error> {
+    let current_admin: Address = e
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::NotInitialized)?;
+    if current_admin != *admin {
+        return Err(Error::InvalidCaller);
+    }
+
+    Ok(())
 }
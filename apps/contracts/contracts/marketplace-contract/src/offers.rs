@@ -0,0 +1,175 @@
+use soroban_sdk::{Address, BytesN, Env, Map, Symbol, Vec};
+
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token, withdraw_escrow};
+use crate::fees::pay_out_sale;
+use crate::history::record_trade;
+use crate::registry::{get_asset, transfer_from_owner};
+use crate::storage::{get_buy_offers, is_listed, set_buy_offers};
+use crate::utils::{BuyOffer, Error};
+
+pub fn make_offer(
+    e: Env,
+    item_id: BytesN<32>,
+    buyer: Address,
+    price: i128,
+    token: Address,
+    expires_at: u64,
+) -> Result<(), Error> {
+    buyer.require_auth();
+
+    internal_check_can_trade(&e, &buyer)?;
+
+    if price <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if expires_at <= e.ledger().timestamp() {
+        return Err(Error::InvalidEndTime);
+    }
+
+    internal_check_token(&e, &token)?;
+
+    let asset = get_asset(&e, &item_id)?;
+    if asset.owner == buyer {
+        return Err(Error::InvalidCaller);
+    }
+
+    let mut offers = get_buy_offers(&e, &item_id);
+
+    if offers.contains_key(buyer.clone()) {
+        return Err(Error::OfferAlreadyExists);
+    }
+
+    // The offer is binding: funds stay escrowed until it is accepted, rejected or withdrawn
+    deposit_escrow(&e, buyer.clone(), token.clone(), price)?;
+
+    let offer = BuyOffer {
+        item_id: item_id.clone(),
+        buyer: buyer.clone(),
+        price,
+        token,
+        expires_at,
+    };
+
+    offers.set(buyer, offer.clone());
+    set_buy_offers(&e, &item_id, &offers);
+
+    e.events()
+        .publish((Symbol::new(&e, "offer_made"), item_id), offer);
+
+    Ok(())
+}
+
+pub fn accept_offer(
+    e: Env,
+    item_id: BytesN<32>,
+    owner: Address,
+    buyer: Address,
+) -> Result<(), Error> {
+    owner.require_auth();
+
+    internal_check_can_trade(&e, &owner)?;
+    internal_check_can_trade(&e, &buyer)?;
+
+    // Listed items sit in marketplace custody; the owner has to delist first
+    if is_listed(&e, &item_id) {
+        return Err(Error::ItemAlreadyListed);
+    }
+
+    let mut offers = get_buy_offers(&e, &item_id);
+
+    let offer = match offers.get(buyer.clone()) {
+        Some(x) => x,
+        None => return Err(Error::OfferNotFound),
+    };
+
+    if offer.expires_at <= e.ledger().timestamp() {
+        return Err(Error::OfferExpired);
+    }
+
+    transfer_from_owner(&e, &item_id, &owner, &buyer, b"marketplace_offer")?;
+
+    let split = pay_out_sale(
+        &e,
+        &item_id,
+        buyer.clone(),
+        owner.clone(),
+        offer.token.clone(),
+        offer.price,
+    )?;
+
+    offers.remove(buyer.clone());
+    set_buy_offers(&e, &item_id, &offers);
+
+    e.events().publish(
+        (Symbol::new(&e, "offer_accepted"), item_id.clone()),
+        (owner.clone(), buyer.clone(), offer.price),
+    );
+
+    record_trade(&e, item_id, owner, buyer, offer.token, offer.price, &split)
+}
+
+pub fn reject_offer(
+    e: Env,
+    item_id: BytesN<32>,
+    owner: Address,
+    buyer: Address,
+) -> Result<(), Error> {
+    owner.require_auth();
+
+    let asset = get_asset(&e, &item_id)?;
+    if asset.owner != owner {
+        return Err(Error::NotItemOwner);
+    }
+
+    internal_close_offer(&e, &item_id, &buyer)?;
+
+    e.events()
+        .publish((Symbol::new(&e, "offer_rejected"), item_id), (owner, buyer));
+
+    Ok(())
+}
+
+// The buyer can pull back an offer that hasn't been accepted, expired or not
+pub fn withdraw_offer(e: Env, item_id: BytesN<32>, buyer: Address) -> Result<(), Error> {
+    buyer.require_auth();
+
+    internal_close_offer(&e, &item_id, &buyer)?;
+
+    e.events()
+        .publish((Symbol::new(&e, "offer_withdrawn"), item_id), buyer);
+
+    Ok(())
+}
+
+pub fn get_offers(e: Env, item_id: BytesN<32>) -> Vec<BuyOffer> {
+    get_buy_offers(&e, &item_id).values()
+}
+
+// Refund every open offer on an item, e.g. once an admin has pulled it
+pub fn internal_close_all_offers(e: &Env, item_id: &BytesN<32>) -> Result<(), Error> {
+    for offer in get_buy_offers(e, item_id).values() {
+        withdraw_escrow(e, offer.buyer, offer.token, offer.price)?;
+    }
+
+    set_buy_offers(e, item_id, &Map::new(e));
+
+    Ok(())
+}
+
+// Refund the buyer's escrowed funds and drop the offer
+fn internal_close_offer(e: &Env, item_id: &BytesN<32>, buyer: &Address) -> Result<(), Error> {
+    let mut offers = get_buy_offers(e, item_id);
+
+    let offer = match offers.get(buyer.clone()) {
+        Some(x) => x,
+        None => return Err(Error::OfferNotFound),
+    };
+
+    withdraw_escrow(e, buyer.clone(), offer.token, offer.price)?;
+
+    offers.remove(buyer.clone());
+    set_buy_offers(e, item_id, &offers);
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol};
+
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token};
+use crate::fees::pay_out_sale;
+use crate::history::record_trade;
+use crate::registry::transfer_approved;
+use crate::storage::{read, write};
+use crate::utils::{DataKey, Error, Order};
+
+// Key the seller signs orders with. Registering it and making the marketplace a registry
+// operator are the seller's only on-chain steps
+pub fn set_order_signer(e: Env, seller: Address, public_key: BytesN<32>) -> Result<(), Error> {
+    seller.require_auth();
+
+    write(&e, &DataKey::OrderSigner(seller.clone()), &public_key);
+
+    e.events()
+        .publish((Symbol::new(&e, "order_signer_set"), seller), public_key);
+
+    Ok(())
+}
+
+pub fn get_order_signer(e: Env, seller: Address) -> Option<BytesN<32>> {
+    read(&e, &DataKey::OrderSigner(seller))
+}
+
+// Settle a seller-signed order. The item moves straight from the seller to the buyer, so the
+// seller must have approved this marketplace for it (or made it an operator) in the registry.
+// A bad signature traps in the host rather than returning an error
+pub fn fill_order(
+    e: Env,
+    order: Order,
+    signature: BytesN<64>,
+    buyer: Address,
+) -> Result<(), Error> {
+    buyer.require_auth();
+
+    internal_check_can_trade(&e, &buyer)?;
+
+    if order.seller == buyer {
+        return Err(Error::InvalidCaller);
+    }
+    if order.price <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if order.expires_at <= e.ledger().timestamp() {
+        return Err(Error::OrderExpired);
+    }
+    internal_check_can_trade(&e, &order.seller)?;
+    internal_check_token(&e, &order.token)?;
+
+    let nonce_key = DataKey::OrderNonce(order.seller.clone(), order.nonce);
+    if e.storage().persistent().has(&nonce_key) {
+        return Err(Error::OrderNonceUsed);
+    }
+
+    let public_key: BytesN<32> = match read(&e, &DataKey::OrderSigner(order.seller.clone())) {
+        Some(x) => x,
+        None => return Err(Error::OrderSignerNotSet),
+    };
+
+    e.crypto().ed25519_verify(
+        &public_key,
+        &order_payload(e.clone(), order.clone()),
+        &signature,
+    );
+
+    write(&e, &nonce_key, &true);
+
+    deposit_escrow(&e, buyer.clone(), order.token.clone(), order.price)?;
+
+    transfer_approved(
+        &e,
+        &order.item_id,
+        &order.seller,
+        &buyer,
+        b"marketplace_order",
+    )?;
+
+    let split = pay_out_sale(
+        &e,
+        &order.item_id,
+        buyer.clone(),
+        order.seller.clone(),
+        order.token.clone(),
+        order.price,
+    )?;
+
+    e.events().publish(
+        (Symbol::new(&e, "order_filled"), order.item_id.clone()),
+        (
+            order.seller.clone(),
+            buyer.clone(),
+            order.nonce,
+            order.price,
+        ),
+    );
+
+    record_trade(
+        &e,
+        order.item_id,
+        order.seller,
+        buyer,
+        order.token,
+        order.price,
+        &split,
+    )
+}
+
+// Burn a nonce so any order signed with it can no longer be filled
+pub fn cancel_order(e: Env, seller: Address, nonce: u64) -> Result<(), Error> {
+    seller.require_auth();
+
+    let nonce_key = DataKey::OrderNonce(seller.clone(), nonce);
+    if e.storage().persistent().has(&nonce_key) {
+        return Err(Error::OrderNonceUsed);
+    }
+
+    write(&e, &nonce_key, &true);
+
+    e.events()
+        .publish((Symbol::new(&e, "order_cancelled"), seller), nonce);
+
+    Ok(())
+}
+
+pub fn is_order_nonce_used(e: Env, seller: Address, nonce: u64) -> bool {
+    e.storage()
+        .persistent()
+        .has(&DataKey::OrderNonce(seller, nonce))
+}
+
+// Bytes the seller signs: this contract's address followed by the order, both as XDR,
+// so an order can't be replayed against another marketplace deployment
+pub fn order_payload(e: Env, order: Order) -> Bytes {
+    let mut payload = e.current_contract_address().to_xdr(&e);
+    payload.append(&order.to_xdr(&e));
+
+    payload
+}
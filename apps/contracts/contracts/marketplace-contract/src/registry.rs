@@ -0,0 +1,124 @@
+use crate::utils::{DataKey, Error, GameAsset};
+use soroban_sdk::{contractclient, Address, Bytes, BytesN, Env};
+
+#[contractclient(name = "AssetRegistryClient")]
+pub trait AssetRegistry {
+    fn asset_exists(env: Env, asset_id: BytesN<32>) -> bool;
+    fn get_asset_info(env: Env, asset_id: BytesN<32>) -> GameAsset;
+    fn transfer_asset(env: Env, from: Address, to: Address, asset_id: BytesN<32>, reason: Bytes);
+    fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        asset_id: BytesN<32>,
+        reason: Bytes,
+    );
+}
+
+pub fn internal_get_registry(e: &Env) -> Result<Address, Error> {
+    let registry_key = DataKey::AssetRegistry;
+
+    match e.storage().instance().get(&registry_key) {
+        Some(addr) => Ok(addr),
+        None => Err(Error::NotInitialized),
+    }
+}
+
+pub fn get_asset(e: &Env, item_id: &BytesN<32>) -> Result<GameAsset, Error> {
+    let registry_address = internal_get_registry(e)?;
+    let client = AssetRegistryClient::new(e, &registry_address);
+
+    if !client.asset_exists(item_id) {
+        return Err(Error::ItemNotFound);
+    }
+
+    Ok(client.get_asset_info(item_id))
+}
+
+// Check that `owner` currently holds the item in the registry
+pub fn verify_owner(e: &Env, item_id: &BytesN<32>, owner: &Address) -> Result<(), Error> {
+    let asset = get_asset(e, item_id)?;
+
+    if asset.owner != *owner {
+        return Err(Error::NotItemOwner);
+    }
+
+    Ok(())
+}
+
+// Move the item from its owner into the marketplace while it is listed
+pub fn take_custody(e: &Env, item_id: &BytesN<32>, owner: &Address) -> Result<(), Error> {
+    verify_owner(e, item_id, owner)?;
+
+    let registry_address = internal_get_registry(e)?;
+    let client = AssetRegistryClient::new(e, &registry_address);
+    client.transfer_asset(
+        owner,
+        &e.current_contract_address(),
+        item_id,
+        &Bytes::from_slice(e, b"marketplace_listing"),
+    );
+
+    Ok(())
+}
+
+// Move an item the marketplace doesn't hold straight from its owner to `to`
+pub fn transfer_from_owner(
+    e: &Env,
+    item_id: &BytesN<32>,
+    owner: &Address,
+    to: &Address,
+    reason: &[u8],
+) -> Result<(), Error> {
+    verify_owner(e, item_id, owner)?;
+
+    let registry_address = internal_get_registry(e)?;
+    let client = AssetRegistryClient::new(e, &registry_address);
+    client.transfer_asset(owner, to, item_id, &Bytes::from_slice(e, reason));
+
+    Ok(())
+}
+
+// Move an item from its owner to `to` under an approval the owner gave the marketplace
+// in the registry, without the owner signing this transaction
+pub fn transfer_approved(
+    e: &Env,
+    item_id: &BytesN<32>,
+    owner: &Address,
+    to: &Address,
+    reason: &[u8],
+) -> Result<(), Error> {
+    verify_owner(e, item_id, owner)?;
+
+    let registry_address = internal_get_registry(e)?;
+    let client = AssetRegistryClient::new(e, &registry_address);
+    client.transfer_from(
+        &e.current_contract_address(),
+        owner,
+        to,
+        item_id,
+        &Bytes::from_slice(e, reason),
+    );
+
+    Ok(())
+}
+
+// Move a held item out of the marketplace, recording `reason` in the registry history
+pub fn release_custody(
+    e: &Env,
+    item_id: &BytesN<32>,
+    to: &Address,
+    reason: &[u8],
+) -> Result<(), Error> {
+    let registry_address = internal_get_registry(e)?;
+    let client = AssetRegistryClient::new(e, &registry_address);
+    client.transfer_asset(
+        &e.current_contract_address(),
+        to,
+        item_id,
+        &Bytes::from_slice(e, reason),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use soroban_sdk::{Address, BytesN, Env, Symbol};
+
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token, release_escrow, unlock_escrow};
+use crate::fees::pay_out_sale;
+use crate::registry::{release_custody, take_custody};
+use crate::storage::{get_rental, is_listed, remove_rental, set_listed, set_rental};
+use crate::utils::{Error, Rental, RentalTerms};
+
+pub fn list_for_rent(
+    e: Env,
+    item_id: BytesN<32>,
+    owner: Address,
+    terms: RentalTerms,
+) -> Result<(), Error> {
+    owner.require_auth();
+
+    internal_check_can_trade(&e, &owner)?;
+
+    if terms.price_per_period <= 0 || terms.collateral <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if terms.period == 0 || terms.max_periods == 0 {
+        return Err(Error::InvalidEndTime);
+    }
+
+    internal_check_token(&e, &terms.token)?;
+
+    if is_listed(&e, &item_id) {
+        return Err(Error::ItemAlreadyListed);
+    }
+
+    // The marketplace holds the item for as long as it is listed, rented or not
+    take_custody(&e, &item_id, &owner)?;
+
+    let rental = Rental {
+        item_id: item_id.clone(),
+        owner: owner.clone(),
+        terms,
+        renter: None,
+        expires_at: 0,
+    };
+
+    set_rental(&e, &rental);
+    set_listed(&e, &item_id, true);
+
+    e.events()
+        .publish((Symbol::new(&e, "rental_listed"), owner), rental);
+
+    Ok(())
+}
+
+pub fn rent_item(e: Env, item_id: BytesN<32>, renter: Address, periods: u32) -> Result<(), Error> {
+    renter.require_auth();
+
+    internal_check_can_trade(&e, &renter)?;
+
+    let mut rental = internal_get_rental(&e, &item_id)?;
+
+    if internal_is_running(&e, &rental) {
+        return Err(Error::ItemRented);
+    }
+    if rental.owner == renter {
+        return Err(Error::InvalidCaller);
+    }
+    if periods == 0 || periods > rental.terms.max_periods {
+        return Err(Error::InvalidEndTime);
+    }
+    internal_check_can_trade(&e, &rental.owner)?;
+
+    // Settle a previous rental that ran out before starting this one
+    internal_end_rental(&e, &mut rental)?;
+
+    let token = rental.terms.token.clone();
+    let price = rental.terms.price_per_period * periods as i128;
+
+    // Rent goes straight to the owner; the collateral stays locked until the rental ends
+    deposit_escrow(
+        &e,
+        renter.clone(),
+        token.clone(),
+        price + rental.terms.collateral,
+    )?;
+    pay_out_sale(
+        &e,
+        &item_id,
+        renter.clone(),
+        rental.owner.clone(),
+        token,
+        price,
+    )?;
+
+    rental.renter = Some(renter.clone());
+    rental.expires_at = e.ledger().timestamp() + rental.terms.period * periods as u64;
+    set_rental(&e, &rental);
+
+    e.events().publish(
+        (Symbol::new(&e, "item_rented"), item_id),
+        (renter, price, rental.expires_at),
+    );
+
+    Ok(())
+}
+
+// End a rental early, or unlock the collateral of one that has run out
+pub fn return_rental(e: Env, item_id: BytesN<32>, renter: Address) -> Result<(), Error> {
+    renter.require_auth();
+
+    let mut rental = internal_get_rental(&e, &item_id)?;
+
+    match &rental.renter {
+        Some(x) if *x == renter => {}
+        Some(_) => return Err(Error::InvalidCaller),
+        None => return Err(Error::ItemNotRented),
+    }
+
+    // The listing stays up for the next renter
+    internal_end_rental(&e, &mut rental)?;
+    set_rental(&e, &rental);
+
+    e.events()
+        .publish((Symbol::new(&e, "rental_returned"), item_id), renter);
+
+    Ok(())
+}
+
+// Pay the renter's collateral to the owner and end the rental; the admin calls this
+// when a game reports the renter misused the item
+pub fn forfeit_rental_collateral(e: &Env, item_id: &BytesN<32>) -> Result<(), Error> {
+    let mut rental = internal_get_rental(e, item_id)?;
+
+    let renter = match rental.renter.take() {
+        Some(x) => x,
+        None => return Err(Error::ItemNotRented),
+    };
+
+    release_escrow(
+        e,
+        renter.clone(),
+        rental.owner.clone(),
+        rental.terms.token.clone(),
+        rental.terms.collateral,
+    )?;
+
+    rental.expires_at = 0;
+    set_rental(e, &rental);
+
+    e.events().publish(
+        (
+            Symbol::new(e, "rental_collateral_forfeited"),
+            item_id.clone(),
+        ),
+        (rental.owner, renter, rental.terms.collateral),
+    );
+
+    Ok(())
+}
+
+// Take down a rental listing once no rental is running
+pub fn cancel_rental(e: Env, item_id: BytesN<32>, owner: Address) -> Result<(), Error> {
+    owner.require_auth();
+
+    let mut rental = internal_get_rental(&e, &item_id)?;
+
+    if rental.owner != owner {
+        return Err(Error::InvalidCaller);
+    }
+    if internal_is_running(&e, &rental) {
+        return Err(Error::ItemRented);
+    }
+
+    internal_end_rental(&e, &mut rental)?;
+    internal_close_rental(&e, &rental, b"marketplace_rental_cancelled")?;
+
+    e.events()
+        .publish((Symbol::new(&e, "rental_cancelled"), item_id), owner);
+
+    Ok(())
+}
+
+// Cuts short any running rental; the renter gets the collateral back but not the rent
+pub fn internal_force_delist(e: &Env, mut rental: Rental) -> Result<Address, Error> {
+    internal_end_rental(e, &mut rental)?;
+    internal_close_rental(e, &rental, b"marketplace_force_delisted")?;
+
+    Ok(rental.owner)
+}
+
+pub fn get_rental_by_id(e: Env, item_id: BytesN<32>) -> Result<Rental, Error> {
+    internal_get_rental(&e, &item_id)
+}
+
+// Who may use the item right now; game servers check this rather than registry
+// ownership, which stays with the marketplace while the item is listed
+pub fn get_renter(e: Env, item_id: BytesN<32>) -> Option<Address> {
+    let rental = get_rental(&e, &item_id)?;
+
+    if !internal_is_running(&e, &rental) {
+        return None;
+    }
+
+    rental.renter
+}
+
+fn internal_is_running(e: &Env, rental: &Rental) -> bool {
+    rental.renter.is_some() && e.ledger().timestamp() < rental.expires_at
+}
+
+// Clear the renter and credit their collateral to their available escrow balance
+fn internal_end_rental(e: &Env, rental: &mut Rental) -> Result<(), Error> {
+    if let Some(renter) = rental.renter.take() {
+        unlock_escrow(
+            e,
+            renter,
+            rental.terms.token.clone(),
+            rental.terms.collateral,
+        )?;
+    }
+    rental.expires_at = 0;
+
+    Ok(())
+}
+
+fn internal_close_rental(e: &Env, rental: &Rental, reason: &[u8]) -> Result<(), Error> {
+    release_custody(e, &rental.item_id, &rental.owner, reason)?;
+
+    remove_rental(e, &rental.item_id);
+    set_listed(e, &rental.item_id, false);
+
+    Ok(())
+}
+
+fn internal_get_rental(e: &Env, item_id: &BytesN<32>) -> Result<Rental, Error> {
+    match get_rental(e, item_id) {
+        Some(x) => Ok(x),
+        None => Err(Error::RentalNotFound),
+    }
+}
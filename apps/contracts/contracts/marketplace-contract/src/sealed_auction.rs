@@ -0,0 +1,382 @@
+use soroban_sdk::{Address, Bytes, BytesN, Env, Map, Symbol};
+
+use crate::admin::{internal_check_can_trade, internal_check_not_paused};
+use crate::escrow::{deposit_escrow, internal_check_token, release_escrow, unlock_escrow};
+use crate::fees::pay_out_sale;
+use crate::history::record_trade;
+use crate::registry::{release_custody, take_custody};
+use crate::storage::{
+    get_sealed_auction, get_sealed_bids, is_listed, remove_sealed_auction, set_listed,
+    set_sealed_auction, set_sealed_bids,
+};
+use crate::utils::{
+    Error, SealedAuction, SealedAuctionConfig, SealedBid, SealedPhase, SealedPricing,
+};
+
+// Bounds the SealedBids entry so reveals and claims stay cheap
+const MAX_SEALED_BIDS: u32 = 100;
+
+pub fn start_sealed_auction(
+    e: Env,
+    item_id: BytesN<32>,
+    seller: Address,
+    config: SealedAuctionConfig,
+) -> Result<(), Error> {
+    seller.require_auth();
+
+    internal_check_can_trade(&e, &seller)?;
+
+    if config.reserve_price < 0 || config.deposit <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if config.commit_duration == 0 || config.reveal_duration == 0 {
+        return Err(Error::InvalidEndTime);
+    }
+
+    internal_check_token(&e, &config.token)?;
+
+    if is_listed(&e, &item_id) {
+        return Err(Error::ItemAlreadyListed);
+    }
+    // A finished auction keeps its record until every bid has been claimed
+    if get_sealed_auction(&e, &item_id).is_some() {
+        return Err(Error::BidsNotClaimed);
+    }
+
+    // Hold the item in the marketplace until the auction is finalized
+    take_custody(&e, &item_id, &seller)?;
+
+    let commit_end = e.ledger().timestamp() + config.commit_duration;
+
+    let new_auction = SealedAuction {
+        item_id: item_id.clone(),
+        seller: seller.clone(),
+        token: config.token,
+        reserve_price: config.reserve_price,
+        deposit: config.deposit,
+        pricing: config.pricing,
+        commit_end,
+        reveal_end: commit_end + config.reveal_duration,
+        highest_bid: 0,
+        highest_bidder: seller.clone(),
+        second_bid: 0,
+        is_active: true,
+    };
+
+    set_sealed_auction(&e, &new_auction);
+    set_listed(&e, &item_id, true);
+
+    e.events().publish(
+        (Symbol::new(&e, "Sealed_auction_started"), seller),
+        new_auction,
+    );
+
+    Ok(())
+}
+
+pub fn commit_bid(
+    e: Env,
+    item_id: BytesN<32>,
+    bidder: Address,
+    commitment: BytesN<32>,
+) -> Result<(), Error> {
+    bidder.require_auth();
+
+    internal_check_can_trade(&e, &bidder)?;
+
+    let auction = internal_get_sealed_auction(&e, &item_id)?;
+
+    if internal_phase(&e, &auction) != SealedPhase::Commit {
+        return Err(Error::WrongAuctionPhase);
+    }
+    if auction.seller == bidder {
+        return Err(Error::CannotBidOnOwnAuction);
+    }
+
+    let mut bids = get_sealed_bids(&e, &item_id);
+
+    if bids.contains_key(bidder.clone()) {
+        return Err(Error::AlreadyCommitted);
+    }
+    if bids.len() >= MAX_SEALED_BIDS {
+        return Err(Error::TooManyBids);
+    }
+
+    // The deposit is forfeited to the seller if the bid is never revealed
+    deposit_escrow(&e, bidder.clone(), auction.token.clone(), auction.deposit)?;
+
+    bids.set(
+        bidder.clone(),
+        SealedBid {
+            commitment,
+            locked: auction.deposit,
+            bid: 0,
+            revealed: false,
+        },
+    );
+    set_sealed_bids(&e, &item_id, &bids);
+
+    e.events()
+        .publish((Symbol::new(&e, "sealed_bid_committed"), item_id), bidder);
+
+    Ok(())
+}
+
+pub fn reveal_bid(
+    e: Env,
+    item_id: BytesN<32>,
+    bidder: Address,
+    bid: i128,
+    salt: Bytes,
+) -> Result<(), Error> {
+    bidder.require_auth();
+
+    if bid <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+
+    let mut auction = internal_get_sealed_auction(&e, &item_id)?;
+
+    if internal_phase(&e, &auction) != SealedPhase::Reveal {
+        return Err(Error::WrongAuctionPhase);
+    }
+
+    let mut bids = get_sealed_bids(&e, &item_id);
+
+    let mut sealed_bid = match bids.get(bidder.clone()) {
+        Some(x) => x,
+        None => return Err(Error::CommitmentNotFound),
+    };
+
+    if sealed_bid.revealed {
+        return Err(Error::AlreadyRevealed);
+    }
+
+    let mut preimage = Bytes::from_array(&e, &bid.to_be_bytes());
+    preimage.append(&salt);
+    let hash: BytesN<32> = e.crypto().sha256(&preimage).into();
+
+    if hash != sealed_bid.commitment {
+        return Err(Error::CommitmentMismatch);
+    }
+
+    // Lock the full bid so the winner can always pay
+    if bid > sealed_bid.locked {
+        deposit_escrow(
+            &e,
+            bidder.clone(),
+            auction.token.clone(),
+            bid - sealed_bid.locked,
+        )?;
+        sealed_bid.locked = bid;
+    }
+
+    sealed_bid.bid = bid;
+    sealed_bid.revealed = true;
+    bids.set(bidder.clone(), sealed_bid);
+    set_sealed_bids(&e, &item_id, &bids);
+
+    if bid > auction.highest_bid {
+        auction.second_bid = auction.highest_bid;
+        auction.highest_bid = bid;
+        auction.highest_bidder = bidder.clone();
+    } else if bid > auction.second_bid {
+        auction.second_bid = bid;
+    }
+    set_sealed_auction(&e, &auction);
+
+    e.events().publish(
+        (Symbol::new(&e, "sealed_bid_revealed"), item_id),
+        (bidder, bid),
+    );
+
+    Ok(())
+}
+
+// Anyone may finalize once the reveal phase is over. Only the winner is settled
+// here; every other bid is settled through claim_sealed_bid so this call stays
+// the same size however many bids were committed
+pub fn finalize_sealed_auction(e: Env, item_id: BytesN<32>) -> Result<(), Error> {
+    internal_check_not_paused(&e)?;
+
+    let mut auction = internal_get_sealed_auction(&e, &item_id)?;
+
+    if internal_phase(&e, &auction) != SealedPhase::Ended {
+        return Err(Error::AuctionStillRunning);
+    }
+
+    let sold =
+        auction.highest_bidder != auction.seller && auction.highest_bid >= auction.reserve_price;
+
+    let price = match auction.pricing {
+        SealedPricing::FirstPrice => auction.highest_bid,
+        SealedPricing::SecondPrice => auction.second_bid.max(auction.reserve_price),
+    };
+
+    let mut bids = get_sealed_bids(&e, &item_id);
+    let mut split = None;
+
+    if sold {
+        let winning_bid = match bids.get(auction.highest_bidder.clone()) {
+            Some(x) => x,
+            None => return Err(Error::CommitmentNotFound),
+        };
+
+        split = Some(pay_out_sale(
+            &e,
+            &item_id,
+            auction.highest_bidder.clone(),
+            auction.seller.clone(),
+            auction.token.clone(),
+            price,
+        )?);
+        if winning_bid.locked > price {
+            unlock_escrow(
+                &e,
+                auction.highest_bidder.clone(),
+                auction.token.clone(),
+                winning_bid.locked - price,
+            )?;
+        }
+        bids.remove(auction.highest_bidder.clone());
+
+        release_custody(
+            &e,
+            &item_id,
+            &auction.highest_bidder,
+            b"marketplace_sealed_auction",
+        )?;
+    } else {
+        release_custody(&e, &item_id, &auction.seller, b"marketplace_auction_unsold")?;
+    }
+
+    auction.is_active = false;
+    internal_store_closed(&e, &auction, &bids);
+    set_listed(&e, &item_id, false);
+
+    if let Some(split) = split {
+        record_trade(
+            &e,
+            item_id.clone(),
+            auction.seller.clone(),
+            auction.highest_bidder.clone(),
+            auction.token.clone(),
+            price,
+            &split,
+        )?;
+    }
+
+    e.events().publish(
+        (Symbol::new(&e, "Sealed_auction_finalized"), item_id),
+        (auction.seller, auction.highest_bidder, price, sold),
+    );
+
+    Ok(())
+}
+
+// Settle one bid of a finalized auction. Revealed bids go back to the bidder's
+// available escrow balance and unrevealed deposits go to the seller, so anyone
+// may call this for any bidder
+pub fn claim_sealed_bid(e: Env, item_id: BytesN<32>, bidder: Address) -> Result<(), Error> {
+    let auction = match get_sealed_auction(&e, &item_id) {
+        Some(x) => x,
+        None => return Err(Error::AuctionNotFound),
+    };
+
+    if auction.is_active {
+        return Err(Error::AuctionStillRunning);
+    }
+
+    let mut bids = get_sealed_bids(&e, &item_id);
+
+    let sealed_bid = match bids.get(bidder.clone()) {
+        Some(x) => x,
+        None => return Err(Error::CommitmentNotFound),
+    };
+
+    if sealed_bid.revealed {
+        unlock_escrow(&e, bidder.clone(), auction.token.clone(), sealed_bid.locked)?;
+    } else {
+        release_escrow(
+            &e,
+            bidder.clone(),
+            auction.seller.clone(),
+            auction.token.clone(),
+            sealed_bid.locked,
+        )?;
+    }
+
+    bids.remove(bidder.clone());
+    internal_store_closed(&e, &auction, &bids);
+
+    e.events().publish(
+        (Symbol::new(&e, "sealed_bid_claimed"), item_id),
+        (bidder, sealed_bid.locked, sealed_bid.revealed),
+    );
+
+    Ok(())
+}
+
+// Return the item to the seller and make every bid, revealed or not, claimable
+// as a refund
+pub fn internal_force_delist(e: &Env, mut auction: SealedAuction) -> Result<Address, Error> {
+    let item_id = auction.item_id.clone();
+
+    let mut bids = get_sealed_bids(e, &item_id);
+    for (bidder, mut sealed_bid) in bids.clone().iter() {
+        if !sealed_bid.revealed {
+            sealed_bid.revealed = true;
+            bids.set(bidder, sealed_bid);
+        }
+    }
+
+    release_custody(e, &item_id, &auction.seller, b"marketplace_force_delisted")?;
+
+    auction.is_active = false;
+    auction.highest_bidder = auction.seller.clone();
+    internal_store_closed(e, &auction, &bids);
+    set_listed(e, &item_id, false);
+
+    Ok(auction.seller)
+}
+
+pub fn get_sealed_auction_phase(e: Env, item_id: BytesN<32>) -> Result<SealedPhase, Error> {
+    let auction = internal_get_sealed_auction(&e, &item_id)?;
+
+    Ok(internal_phase(&e, &auction))
+}
+
+fn internal_phase(e: &Env, auction: &SealedAuction) -> SealedPhase {
+    let current_time = e.ledger().timestamp();
+
+    if current_time < auction.commit_end {
+        SealedPhase::Commit
+    } else if current_time < auction.reveal_end {
+        SealedPhase::Reveal
+    } else {
+        SealedPhase::Ended
+    }
+}
+
+fn internal_get_sealed_auction(e: &Env, item_id: &BytesN<32>) -> Result<SealedAuction, Error> {
+    let auction = match get_sealed_auction(e, item_id) {
+        Some(x) => x,
+        None => return Err(Error::AuctionNotFound),
+    };
+
+    if !auction.is_active {
+        return Err(Error::AuctionNotActive);
+    }
+
+    Ok(auction)
+}
+
+// Keep a closed auction around only while it still has bids to claim
+fn internal_store_closed(e: &Env, auction: &SealedAuction, bids: &Map<Address, SealedBid>) {
+    if bids.is_empty() {
+        remove_sealed_auction(e, &auction.item_id);
+    } else {
+        set_sealed_auction(e, auction);
+        set_sealed_bids(e, &auction.item_id, bids);
+    }
+}
@@ -0,0 +1,275 @@
+use soroban_sdk::{Address, Bytes, BytesN, Env, Vec};
+
+use crate::registry::get_asset;
+use crate::storage::{read, remove, write};
+use crate::utils::{CollectionStats, DataKey, Error, PriceEntry, SalesStats};
+
+const HOUR: u64 = 3_600;
+const DAY: u64 = 86_400;
+
+const HOURLY_BUCKETS: u32 = 24;
+const DAILY_BUCKETS: u32 = 7;
+
+// Expired listings the floor query will step over before giving up
+const FLOOR_SCAN: u32 = 20;
+
+pub fn get_collection_stats(e: Env, item_type: Bytes, token: Address) -> CollectionStats {
+    let now = e.ledger().timestamp();
+    let mut stats = internal_get_stats(&e, &item_type, &token);
+    internal_roll(&mut stats, now);
+
+    CollectionStats {
+        last_sale_price: stats.last_sale_price,
+        last_sale_at: stats.last_sale_at,
+        trade_count: stats.trade_count,
+        floor_price: internal_floor(&e, &item_type, &token),
+        volume_24h: internal_sum(&stats.hourly_volume),
+        volume_7d: internal_sum(&stats.daily_volume),
+    }
+}
+
+pub fn record_sale(
+    e: &Env,
+    item_id: &BytesN<32>,
+    token: &Address,
+    price: i128,
+) -> Result<(), Error> {
+    let item_type = get_asset(e, item_id)?.metadata.item_type;
+    let now = e.ledger().timestamp();
+
+    let mut stats = internal_get_stats(e, &item_type, token);
+    internal_roll(&mut stats, now);
+
+    let hour = ((now / HOUR) % HOURLY_BUCKETS as u64) as u32;
+    let day = ((now / DAY) % DAILY_BUCKETS as u64) as u32;
+    stats
+        .hourly_volume
+        .set(hour, stats.hourly_volume.get(hour).unwrap_or(0) + price);
+    stats
+        .daily_volume
+        .set(day, stats.daily_volume.get(day).unwrap_or(0) + price);
+
+    stats.last_sale_price = price;
+    stats.last_sale_at = now;
+    stats.trade_count += 1;
+
+    write(e, &DataKey::SalesStats(item_type, token.clone()), &stats);
+
+    Ok(())
+}
+
+// Active listing prices per item type and token form a binary min-heap, one persistent
+// entry per slot, so the floor sits at the root and a listing costs O(log n) writes.
+// The item type is saved with the listing so removal hits the same heap even if the
+// registry changes the item's type while it is listed
+pub fn add_listing_price(
+    e: &Env,
+    item_id: &BytesN<32>,
+    token: &Address,
+    price: i128,
+    expires_at: Option<u64>,
+) -> Result<(), Error> {
+    let item_type = get_asset(e, item_id)?.metadata.item_type;
+    write(e, &DataKey::ListedType(item_id.clone()), &item_type);
+
+    let len = internal_heap_len(e, &item_type, token);
+    internal_set_heap_len(e, &item_type, token, len + 1);
+
+    let entry = PriceEntry {
+        item_id: item_id.clone(),
+        price,
+        expires_at,
+    };
+    internal_sift_up(e, &item_type, token, len, entry);
+
+    Ok(())
+}
+
+pub fn remove_listing_price(e: &Env, item_id: &BytesN<32>, token: &Address) -> Result<(), Error> {
+    let listed_type_key = DataKey::ListedType(item_id.clone());
+    let item_type: Bytes = match read(e, &listed_type_key) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    remove(e, &listed_type_key);
+
+    let slot_key = DataKey::PriceSlot(item_id.clone());
+    let slot: u32 = match read(e, &slot_key) {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    remove(e, &slot_key);
+
+    // Move the last entry into the freed slot and restore the heap order around it
+    let last = internal_heap_len(e, &item_type, token) - 1;
+    let last_key = DataKey::PriceHeapAt(item_type.clone(), token.clone(), last);
+    let entry: Option<PriceEntry> = read(e, &last_key);
+    remove(e, &last_key);
+    internal_set_heap_len(e, &item_type, token, last);
+
+    if let Some(entry) = entry.filter(|_| slot < last) {
+        let parent = internal_heap_at(e, &item_type, token, slot.saturating_sub(1) / 2);
+        if slot > 0 && parent.is_some_and(|x| entry.price < x.price) {
+            internal_sift_up(e, &item_type, token, slot, entry);
+        } else {
+            internal_sift_down(e, &item_type, token, slot, last, entry);
+        }
+    }
+
+    Ok(())
+}
+
+fn internal_get_stats(e: &Env, item_type: &Bytes, token: &Address) -> SalesStats {
+    match read(e, &DataKey::SalesStats(item_type.clone(), token.clone())) {
+        Some(x) => x,
+        None => SalesStats {
+            last_sale_price: 0,
+            last_sale_at: 0,
+            trade_count: 0,
+            updated_at: e.ledger().timestamp(),
+            hourly_volume: Vec::from_array(e, [0; HOURLY_BUCKETS as usize]),
+            daily_volume: Vec::from_array(e, [0; DAILY_BUCKETS as usize]),
+        },
+    }
+}
+
+// Cheapest listing that hasn't expired. Expired listings stay in the heap until they are
+// purged, so walk it cheapest-first past them, giving up after `FLOOR_SCAN` entries
+fn internal_floor(e: &Env, item_type: &Bytes, token: &Address) -> Option<i128> {
+    let now = e.ledger().timestamp();
+
+    // (price, slot) of heap entries whose parents have been passed over
+    let mut frontier: Vec<(i128, u32)> = Vec::new(e);
+    if let Some(root) = internal_heap_at(e, item_type, token, 0) {
+        frontier.push_back((root.price, 0));
+    }
+
+    for _ in 0..FLOOR_SCAN {
+        let mut cheapest: Option<(u32, i128, u32)> = None;
+        for (i, (price, slot)) in frontier.iter().enumerate() {
+            if cheapest.is_none_or(|(_, x, _)| price < x) {
+                cheapest = Some((i as u32, price, slot));
+            }
+        }
+        let (i, price, slot) = cheapest?;
+        frontier.remove(i);
+
+        let entry = internal_heap_at(e, item_type, token, slot)?;
+        if entry.expires_at.is_none_or(|x| x > now) {
+            return Some(price);
+        }
+
+        for child in [2 * slot + 1, 2 * slot + 2] {
+            if let Some(x) = internal_heap_at(e, item_type, token, child) {
+                frontier.push_back((x.price, child));
+            }
+        }
+    }
+
+    None
+}
+
+fn internal_heap_len(e: &Env, item_type: &Bytes, token: &Address) -> u32 {
+    read(e, &DataKey::PriceHeapLen(item_type.clone(), token.clone())).unwrap_or(0)
+}
+
+fn internal_set_heap_len(e: &Env, item_type: &Bytes, token: &Address, len: u32) {
+    let key = DataKey::PriceHeapLen(item_type.clone(), token.clone());
+    if len == 0 {
+        remove(e, &key);
+    } else {
+        write(e, &key, &len);
+    }
+}
+
+fn internal_heap_at(e: &Env, item_type: &Bytes, token: &Address, slot: u32) -> Option<PriceEntry> {
+    read(
+        e,
+        &DataKey::PriceHeapAt(item_type.clone(), token.clone(), slot),
+    )
+}
+
+fn internal_heap_set(e: &Env, item_type: &Bytes, token: &Address, slot: u32, entry: &PriceEntry) {
+    write(
+        e,
+        &DataKey::PriceHeapAt(item_type.clone(), token.clone(), slot),
+        entry,
+    );
+    write(e, &DataKey::PriceSlot(entry.item_id.clone()), &slot);
+}
+
+// Carry `entry` from `slot` towards the root past every dearer parent
+fn internal_sift_up(e: &Env, item_type: &Bytes, token: &Address, slot: u32, entry: PriceEntry) {
+    let mut slot = slot;
+    while slot > 0 {
+        let parent_slot = (slot - 1) / 2;
+        let parent = match internal_heap_at(e, item_type, token, parent_slot) {
+            Some(x) if x.price > entry.price => x,
+            _ => break,
+        };
+        internal_heap_set(e, item_type, token, slot, &parent);
+        slot = parent_slot;
+    }
+
+    internal_heap_set(e, item_type, token, slot, &entry);
+}
+
+// Carry `entry` from `slot` towards the leaves past every cheaper child
+fn internal_sift_down(
+    e: &Env,
+    item_type: &Bytes,
+    token: &Address,
+    slot: u32,
+    len: u32,
+    entry: PriceEntry,
+) {
+    let mut slot = slot;
+    loop {
+        let mut child_slot = 2 * slot + 1;
+        if child_slot >= len {
+            break;
+        }
+        let mut child = match internal_heap_at(e, item_type, token, child_slot) {
+            Some(x) => x,
+            None => break,
+        };
+        if let Some(right) = internal_heap_at(e, item_type, token, child_slot + 1) {
+            if child_slot + 1 < len && right.price < child.price {
+                child = right;
+                child_slot += 1;
+            }
+        }
+        if child.price >= entry.price {
+            break;
+        }
+        internal_heap_set(e, item_type, token, slot, &child);
+        slot = child_slot;
+    }
+
+    internal_heap_set(e, item_type, token, slot, &entry);
+}
+
+// Zero the buckets that have fallen out of the window since the last update
+fn internal_roll(stats: &mut SalesStats, now: u64) {
+    internal_roll_buckets(&mut stats.hourly_volume, HOUR, stats.updated_at, now);
+    internal_roll_buckets(&mut stats.daily_volume, DAY, stats.updated_at, now);
+    stats.updated_at = now;
+}
+
+fn internal_roll_buckets(buckets: &mut Vec<i128>, width: u64, from: u64, to: u64) {
+    let len = buckets.len() as u64;
+    let start = from / width;
+    let end = to / width;
+
+    if end <= start {
+        return;
+    }
+
+    for step in 1..=(end - start).min(len) {
+        buckets.set(((start + step) % len) as u32, 0);
+    }
+}
+
+fn internal_sum(buckets: &Vec<i128>) -> i128 {
+    buckets.iter().sum()
+}
@@ -0,0 +1,257 @@
+use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal, Map, TryFromVal, Val, Vec};
+
+use crate::utils::{
+    Auction, BuyOffer, DataKey, DutchAuction, Error, EscrowBalance, LegacyDataKey, Rental, Royalty,
+    SealedAuction, SealedBid, SwapOffer, TradeHistory, TradeOffer,
+};
+
+// Ledgers close roughly every 5 seconds
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+// Every read of a live entry pushes its expiry out, so active listings never archive
+pub fn read<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> Option<V> {
+    let value = e.storage().persistent().get(key);
+    if value.is_some() {
+        e.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+    value
+}
+
+pub fn write<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+    e.storage().persistent().set(key, value);
+    e.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    extend_instance(e);
+}
+
+pub fn remove(e: &Env, key: &DataKey) {
+    e.storage().persistent().remove(key);
+}
+
+pub fn get_trade_offer(e: &Env, item_id: &BytesN<32>) -> Option<TradeOffer> {
+    read(e, &DataKey::TradeOffer(item_id.clone()))
+}
+
+pub fn set_trade_offer(e: &Env, offer: &TradeOffer) {
+    write(e, &DataKey::TradeOffer(offer.item_id.clone()), offer);
+}
+
+pub fn remove_trade_offer(e: &Env, item_id: &BytesN<32>) {
+    remove(e, &DataKey::TradeOffer(item_id.clone()));
+}
+
+pub fn is_listed(e: &Env, item_id: &BytesN<32>) -> bool {
+    read(e, &DataKey::Listed(item_id.clone())).unwrap_or(false)
+}
+
+pub fn set_listed(e: &Env, item_id: &BytesN<32>, listed: bool) {
+    let key = DataKey::Listed(item_id.clone());
+    if listed {
+        write(e, &key, &true);
+    } else {
+        remove(e, &key);
+    }
+}
+
+pub fn get_auction(e: &Env, item_id: &BytesN<32>) -> Option<Auction> {
+    read(e, &DataKey::Auction(item_id.clone()))
+}
+
+pub fn set_auction(e: &Env, auction: &Auction) {
+    write(e, &DataKey::Auction(auction.item_id.clone()), auction);
+}
+
+pub fn remove_auction(e: &Env, item_id: &BytesN<32>) {
+    remove(e, &DataKey::Auction(item_id.clone()));
+}
+
+pub fn get_dutch_auction(e: &Env, item_id: &BytesN<32>) -> Option<DutchAuction> {
+    read(e, &DataKey::DutchAuction(item_id.clone()))
+}
+
+pub fn set_dutch_auction(e: &Env, auction: &DutchAuction) {
+    write(e, &DataKey::DutchAuction(auction.item_id.clone()), auction);
+}
+
+pub fn remove_dutch_auction(e: &Env, item_id: &BytesN<32>) {
+    remove(e, &DataKey::DutchAuction(item_id.clone()));
+}
+
+pub fn get_rental(e: &Env, item_id: &BytesN<32>) -> Option<Rental> {
+    read(e, &DataKey::Rental(item_id.clone()))
+}
+
+pub fn set_rental(e: &Env, rental: &Rental) {
+    write(e, &DataKey::Rental(rental.item_id.clone()), rental);
+}
+
+pub fn remove_rental(e: &Env, item_id: &BytesN<32>) {
+    remove(e, &DataKey::Rental(item_id.clone()));
+}
+
+pub fn get_sealed_auction(e: &Env, item_id: &BytesN<32>) -> Option<SealedAuction> {
+    read(e, &DataKey::SealedAuction(item_id.clone()))
+}
+
+pub fn set_sealed_auction(e: &Env, auction: &SealedAuction) {
+    write(e, &DataKey::SealedAuction(auction.item_id.clone()), auction);
+}
+
+pub fn remove_sealed_auction(e: &Env, item_id: &BytesN<32>) {
+    remove(e, &DataKey::SealedAuction(item_id.clone()));
+    remove(e, &DataKey::SealedBids(item_id.clone()));
+}
+
+pub fn get_sealed_bids(e: &Env, item_id: &BytesN<32>) -> Map<Address, SealedBid> {
+    read(e, &DataKey::SealedBids(item_id.clone())).unwrap_or(Map::new(e))
+}
+
+pub fn set_sealed_bids(e: &Env, item_id: &BytesN<32>, bids: &Map<Address, SealedBid>) {
+    write(e, &DataKey::SealedBids(item_id.clone()), bids);
+}
+
+pub fn get_buy_offers(e: &Env, item_id: &BytesN<32>) -> Map<Address, BuyOffer> {
+    read(e, &DataKey::BuyOffers(item_id.clone())).unwrap_or(Map::new(e))
+}
+
+pub fn set_buy_offers(e: &Env, item_id: &BytesN<32>, offers: &Map<Address, BuyOffer>) {
+    let key = DataKey::BuyOffers(item_id.clone());
+    if offers.is_empty() {
+        remove(e, &key);
+    } else {
+        write(e, &key, offers);
+    }
+}
+
+pub fn get_swap(e: &Env, swap_id: u64) -> Option<SwapOffer> {
+    read(e, &DataKey::Swap(swap_id))
+}
+
+pub fn set_swap(e: &Env, swap: &SwapOffer) {
+    write(e, &DataKey::Swap(swap.swap_id), swap);
+}
+
+pub fn remove_swap(e: &Env, swap_id: u64) {
+    remove(e, &DataKey::Swap(swap_id));
+}
+
+// The open swap holding an item on the maker's side
+pub fn get_item_swap(e: &Env, item_id: &BytesN<32>) -> Option<u64> {
+    read(e, &DataKey::ItemSwap(item_id.clone()))
+}
+
+pub fn set_item_swap(e: &Env, item_id: &BytesN<32>, swap_id: Option<u64>) {
+    let key = DataKey::ItemSwap(item_id.clone());
+    match swap_id {
+        Some(x) => write(e, &key, &x),
+        None => remove(e, &key),
+    }
+}
+
+pub fn get_trade_history(e: &Env, item_id: &BytesN<32>) -> Vec<TradeHistory> {
+    read(e, &DataKey::TradeHistory(item_id.clone())).unwrap_or(Vec::new(e))
+}
+
+pub fn push_trade_history(e: &Env, item_id: &BytesN<32>, entry: TradeHistory) {
+    let mut history = get_trade_history(e, item_id);
+    history.push_back(entry);
+    write(e, &DataKey::TradeHistory(item_id.clone()), &history);
+}
+
+pub fn get_escrow_balance(e: &Env, user: &Address, token: &Address) -> i128 {
+    let escrow: Option<EscrowBalance> = read(e, &DataKey::Escrow(user.clone(), token.clone()));
+    escrow.map(|x| x.balance).unwrap_or(0)
+}
+
+pub fn set_escrow_balance(e: &Env, user: &Address, token: &Address, balance: i128) {
+    let key = DataKey::Escrow(user.clone(), token.clone());
+    if balance == 0 {
+        remove(e, &key);
+    } else {
+        write(e, &key, &EscrowBalance { balance });
+    }
+}
+
+pub fn get_available_balance(e: &Env, user: &Address, token: &Address) -> i128 {
+    read(e, &DataKey::EscrowAvailable(user.clone(), token.clone())).unwrap_or(0)
+}
+
+pub fn set_available_balance(e: &Env, user: &Address, token: &Address, balance: i128) {
+    let key = DataKey::EscrowAvailable(user.clone(), token.clone());
+    if balance == 0 {
+        remove(e, &key);
+    } else {
+        write(e, &key, &balance);
+    }
+}
+
+pub fn get_item_royalty(e: &Env, item_id: &BytesN<32>) -> Option<Royalty> {
+    read(e, &DataKey::ItemRoyalty(item_id.clone()))
+}
+
+pub fn set_item_royalty(e: &Env, item_id: &BytesN<32>, royalty: &Royalty) {
+    write(e, &DataKey::ItemRoyalty(item_id.clone()), royalty);
+}
+
+pub fn get_collection_royalty(e: &Env, item_type: &Bytes) -> Option<Royalty> {
+    read(e, &DataKey::CollectionRoyalty(item_type.clone()))
+}
+
+pub fn set_collection_royalty(e: &Env, item_type: &Bytes, royalty: &Royalty) {
+    write(e, &DataKey::CollectionRoyalty(item_type.clone()), royalty);
+}
+
+// Bring a deployment of the original contract up to this layout. It kept only the
+// admin and a single payment token under instance keys; it had no way to add items,
+// so its item, listing, auction, history and escrow maps can only be empty and are
+// dropped. The registry it never knew about is set here, once
+pub fn migrate_legacy(e: &Env, asset_registry: &Address) -> Result<(), Error> {
+    let instance = e.storage().instance();
+
+    match instance.get::<_, Address>(&DataKey::AssetRegistry) {
+        Some(x) if x != *asset_registry => return Err(Error::AlreadyInitialized),
+        Some(_) => {}
+        None => instance.set(&DataKey::AssetRegistry, asset_registry),
+    }
+
+    // The single token becomes the first entry of the allowlist
+    if let Some(token) = instance.get::<_, Address>(&LegacyDataKey::TokenAddress) {
+        let mut allowed_tokens: Map<Address, bool> =
+            instance.get(&DataKey::AllowedTokens).unwrap_or(Map::new(e));
+        allowed_tokens.set(token, true);
+        instance.set(&DataKey::AllowedTokens, &allowed_tokens);
+        instance.remove(&LegacyDataKey::TokenAddress);
+    }
+
+    for key in [
+        LegacyDataKey::Items,
+        LegacyDataKey::TradeOffer,
+        LegacyDataKey::Listed,
+        LegacyDataKey::Auction,
+        LegacyDataKey::TradeHistory,
+        LegacyDataKey::Escrow,
+    ] {
+        instance.remove(&key);
+    }
+
+    extend_instance(e);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec};
+
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token, release_escrow, withdraw_escrow};
+use crate::registry::{release_custody, take_custody, transfer_from_owner};
+use crate::storage::{self, is_listed, remove_swap, set_item_swap, set_listed, set_swap};
+use crate::utils::{DataKey, Error, SwapOffer};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_swap(
+    e: Env,
+    maker: Address,
+    taker: Address,
+    maker_items: Vec<BytesN<32>>,
+    taker_items: Vec<BytesN<32>>,
+    token: Address,
+    maker_amount: i128,
+    taker_amount: i128,
+) -> Result<u64, Error> {
+    maker.require_auth();
+
+    internal_check_can_trade(&e, &maker)?;
+
+    if maker == taker {
+        return Err(Error::InvalidCaller);
+    }
+    if maker_amount < 0 || taker_amount < 0 {
+        return Err(Error::InvalidAmount);
+    }
+    // Each side has to give something
+    if (maker_items.is_empty() && maker_amount == 0)
+        || (taker_items.is_empty() && taker_amount == 0)
+    {
+        return Err(Error::InvalidAmount);
+    }
+    if maker_amount > 0 || taker_amount > 0 {
+        internal_check_token(&e, &token)?;
+    }
+
+    let next_swap_id_key = DataKey::NextSwapId;
+    let swap_id: u64 = e.storage().instance().get(&next_swap_id_key).unwrap_or(0);
+
+    for item_id in maker_items.iter() {
+        if is_listed(&e, &item_id) {
+            return Err(Error::ItemAlreadyListed);
+        }
+
+        take_custody(&e, &item_id, &maker)?;
+        set_listed(&e, &item_id, true);
+        set_item_swap(&e, &item_id, Some(swap_id));
+    }
+
+    if maker_amount > 0 {
+        deposit_escrow(&e, maker.clone(), token.clone(), maker_amount)?;
+    }
+
+    let swap = SwapOffer {
+        swap_id,
+        maker: maker.clone(),
+        taker,
+        maker_items,
+        taker_items,
+        token,
+        maker_amount,
+        taker_amount,
+    };
+
+    set_swap(&e, &swap);
+    e.storage()
+        .instance()
+        .set(&next_swap_id_key, &(swap_id + 1));
+
+    e.events()
+        .publish((Symbol::new(&e, "swap_created"), maker), swap);
+
+    Ok(swap_id)
+}
+
+// Swap every item and token in one go; any failed transfer reverts the whole call
+pub fn accept_swap(e: Env, swap_id: u64, taker: Address) -> Result<(), Error> {
+    taker.require_auth();
+
+    internal_check_can_trade(&e, &taker)?;
+
+    let swap = internal_take_swap(&e, swap_id)?;
+
+    if swap.taker != taker {
+        return Err(Error::InvalidCaller);
+    }
+    internal_check_can_trade(&e, &swap.maker)?;
+
+    for item_id in swap.taker_items.iter() {
+        transfer_from_owner(&e, &item_id, &taker, &swap.maker, b"marketplace_swap")?;
+    }
+
+    if swap.taker_amount > 0 {
+        deposit_escrow(&e, taker.clone(), swap.token.clone(), swap.taker_amount)?;
+        release_escrow(
+            &e,
+            taker.clone(),
+            swap.maker.clone(),
+            swap.token.clone(),
+            swap.taker_amount,
+        )?;
+    }
+
+    internal_release_maker_side(&e, &swap, &taker)?;
+
+    e.events().publish(
+        (Symbol::new(&e, "swap_executed"), swap_id),
+        (swap.maker, taker),
+    );
+
+    Ok(())
+}
+
+pub fn cancel_swap(e: Env, swap_id: u64, maker: Address) -> Result<(), Error> {
+    maker.require_auth();
+
+    let swap = internal_take_swap(&e, swap_id)?;
+
+    if swap.maker != maker {
+        return Err(Error::InvalidCaller);
+    }
+
+    // Hand the maker's items and tokens back
+    internal_release_maker_side(&e, &swap, &maker)?;
+
+    e.events()
+        .publish((Symbol::new(&e, "swap_cancelled"), swap_id), maker);
+
+    Ok(())
+}
+
+// Call off the whole swap, handing the maker's items and tokens back
+pub fn internal_force_delist(e: &Env, swap_id: u64) -> Result<Address, Error> {
+    let swap = internal_take_swap(e, swap_id)?;
+
+    internal_release_maker_side(e, &swap, &swap.maker)?;
+
+    Ok(swap.maker)
+}
+
+pub fn get_swap(e: Env, swap_id: u64) -> Result<SwapOffer, Error> {
+    storage::get_swap(&e, swap_id).ok_or(Error::SwapNotFound)
+}
+
+// Load a swap and remove it from storage
+fn internal_take_swap(e: &Env, swap_id: u64) -> Result<SwapOffer, Error> {
+    let swap = match storage::get_swap(e, swap_id) {
+        Some(x) => x,
+        None => return Err(Error::SwapNotFound),
+    };
+
+    remove_swap(e, swap_id);
+
+    Ok(swap)
+}
+
+// Send the maker's held items and escrowed tokens to `to`
+fn internal_release_maker_side(e: &Env, swap: &SwapOffer, to: &Address) -> Result<(), Error> {
+    for item_id in swap.maker_items.iter() {
+        release_custody(e, &item_id, to, b"marketplace_swap")?;
+        set_listed(e, &item_id, false);
+        set_item_swap(e, &item_id, None);
+    }
+
+    if swap.maker_amount > 0 {
+        if *to == swap.maker {
+            withdraw_escrow(e, swap.maker.clone(), swap.token.clone(), swap.maker_amount)?;
+        } else {
+            release_escrow(
+                e,
+                swap.maker.clone(),
+                to.clone(),
+                swap.token.clone(),
+                swap.maker_amount,
+            )?;
+        }
+    }
+
+    Ok(())
+}
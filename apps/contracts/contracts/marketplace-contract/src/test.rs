@@ -0,0 +1,1416 @@
+#![cfg(test)]
+extern crate std;
+
+use super::*;
+use crate::utils::LegacyDataKey;
+use asset_registry::{GameAssetContract, GameAssetContractClient, Role};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token::{StellarAssetClient, TokenClient},
+    Address, Bytes, BytesN, Env, Map, Vec,
+};
+
+const START_TIME: u64 = 1_700_000_000;
+
+struct TestContext {
+    env: Env,
+    admin: Address,
+    market_id: Address,
+    market: ContractMarketplaceClient<'static>,
+    registry: GameAssetContractClient<'static>,
+    token: TokenClient<'static>,
+}
+
+/// Register the real asset registry, a Stellar Asset Contract token and the marketplace
+fn setup_test() -> TestContext {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(START_TIME);
+
+    let admin = Address::generate(&env);
+
+    let registry_id = env.register(GameAssetContract, ());
+    let registry = GameAssetContractClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+    registry.grant_role(&Role::Minter, &admin, &None);
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    let token = TokenClient::new(&env, &token_id);
+
+    let market_id = env.register(ContractMarketplace, ());
+    let market = ContractMarketplaceClient::new(&env, &market_id);
+    market.initialize(&admin, &token_id, &registry_id);
+
+    TestContext {
+        env,
+        admin,
+        market_id,
+        market,
+        registry,
+        token,
+    }
+}
+
+fn mint_item(ctx: &TestContext, owner: &Address, n: u8) -> BytesN<32> {
+    let id = BytesN::from_array(&ctx.env, &[n; 32]);
+    ctx.registry.register_asset(
+        &ctx.admin,
+        owner,
+        &id,
+        &Bytes::from_slice(&ctx.env, b"Sword"),
+        &Bytes::from_slice(&ctx.env, b"weapon"),
+        &Map::new(&ctx.env),
+    );
+    id
+}
+
+fn fund(ctx: &TestContext, user: &Address, amount: i128) {
+    StellarAssetClient::new(&ctx.env, &ctx.token.address).mint(user, &amount);
+}
+
+fn plain_auction() -> AuctionConfig {
+    AuctionConfig {
+        reserve_price: None,
+        min_increment: BidIncrement::AnyHigher,
+        buy_now_price: None,
+        extension_window: 0,
+    }
+}
+
+fn advance(ctx: &TestContext, seconds: u64) {
+    let now = ctx.env.ledger().timestamp();
+    ctx.env.ledger().set_timestamp(now + seconds);
+}
+
+#[test]
+fn test_list_and_buy() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &ctx.token.address, &None);
+
+    // The marketplace holds the item while it is listed
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, ctx.market_id);
+    assert_eq!(ctx.registry.get_user_asset_count(&ctx.market_id), 1);
+    assert_eq!(ctx.market.get_active_listings(&0, &10).offers.len(), 1);
+
+    ctx.market.p2p_execute_trade(&item, &buyer);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+    assert_eq!(ctx.registry.get_user_asset_count(&ctx.market_id), 0);
+    assert_eq!(ctx.token.balance(&buyer), 600);
+    assert_eq!(ctx.token.balance(&seller), 400);
+    assert_eq!(ctx.market.get_trade_history(&item).len(), 1);
+    assert_eq!(ctx.market.get_active_listings(&0, &10).offers.len(), 0);
+}
+
+#[test]
+fn test_list_requires_ownership() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let other = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+
+    let result = ctx
+        .market
+        .try_list_for_sale(&item, &other, &400, &ctx.token.address, &None);
+    assert_eq!(result, Err(Ok(Error::NotItemOwner)));
+}
+
+#[test]
+fn test_delist_returns_item() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &ctx.token.address, &None);
+    ctx.market.delist_from_sale(&item, &seller);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+    assert_eq!(
+        ctx.market.try_get_trade_offer(&item),
+        Err(Ok(Error::TradeOfferNotFound))
+    );
+}
+
+#[test]
+fn test_outbid_refund_credits_escrow() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let first = Address::generate(&ctx.env);
+    let second = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &first, 1_000);
+    fund(&ctx, &second, 1_000);
+
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+    ctx.market.place_auction_bid(&item, &first, &100);
+    ctx.market.place_auction_bid(&item, &second, &150);
+
+    // The outbid refund waits in escrow instead of being pushed back
+    let escrow = ctx.market.get_escrow_balance(&first, &ctx.token.address);
+    assert_eq!(escrow.available, 100);
+    assert_eq!(escrow.locked, 0);
+    assert_eq!(ctx.token.balance(&first), 900);
+
+    ctx.market.withdraw(&first, &ctx.token.address, &100);
+    assert_eq!(ctx.token.balance(&first), 1_000);
+
+    // An available balance is spent before the wallet on the next bid
+    ctx.market.deposit(&first, &ctx.token.address, &200);
+    ctx.market.place_auction_bid(&item, &first, &200);
+    assert_eq!(ctx.token.balance(&first), 800);
+    let escrow = ctx.market.get_escrow_balance(&first, &ctx.token.address);
+    assert_eq!(escrow.available, 0);
+    assert_eq!(escrow.locked, 200);
+}
+
+#[test]
+fn test_settle_auction_with_bids() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let bidder = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &bidder, 1_000);
+
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+    ctx.market.place_auction_bid(&item, &bidder, &300);
+
+    let result = ctx.market.try_settle_auction(&item);
+    assert_eq!(result, Err(Ok(Error::AuctionStillRunning)));
+
+    advance(&ctx, 3_601);
+    ctx.market.settle_auction(&item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, bidder);
+    assert_eq!(ctx.token.balance(&seller), 300);
+    assert_eq!(ctx.token.balance(&bidder), 700);
+}
+
+#[test]
+fn test_settle_auction_without_bids() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+
+    advance(&ctx, 3_601);
+    ctx.market.settle_auction(&item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+    let auction = ctx.market.get_auction(&item);
+    assert!(!auction.is_active);
+    assert_eq!(auction.highest_bidder, seller);
+}
+
+#[test]
+fn test_basis_point_increment_is_bounded() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+
+    let mut config = plain_auction();
+    config.min_increment = BidIncrement::BasisPoints(10_001);
+    let result =
+        ctx.market
+            .try_start_auction(&item, &seller, &50, &3_600, &config, &ctx.token.address);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_reserve_not_met_returns_item() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let bidder = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &bidder, 1_000);
+
+    let mut config = plain_auction();
+    config.reserve_price = Some(500);
+    ctx.market
+        .start_auction(&item, &seller, &50, &3_600, &config, &ctx.token.address);
+    ctx.market.place_auction_bid(&item, &bidder, &300);
+
+    advance(&ctx, 3_601);
+    assert_eq!(
+        ctx.market.try_claim_auction_bid(&item, &bidder),
+        Err(Ok(Error::ReserveNotMet))
+    );
+    ctx.market.settle_auction(&item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+    assert_eq!(ctx.token.balance(&bidder), 1_000);
+    assert_eq!(ctx.token.balance(&seller), 0);
+}
+
+#[test]
+fn test_buy_now_and_minimum_increment() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let first = Address::generate(&ctx.env);
+    let second = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &first, 1_000);
+    fund(&ctx, &second, 1_000);
+
+    let config = AuctionConfig {
+        reserve_price: None,
+        min_increment: BidIncrement::BasisPoints(1_000),
+        buy_now_price: Some(400),
+        extension_window: 0,
+    };
+    ctx.market
+        .start_auction(&item, &seller, &50, &3_600, &config, &ctx.token.address);
+    ctx.market.place_auction_bid(&item, &first, &100);
+
+    // The next bid has to clear 10% over 100
+    assert_eq!(
+        ctx.market.try_place_auction_bid(&item, &second, &109),
+        Err(Ok(Error::BidTooLow))
+    );
+
+    // Bidding past the buy-now price wins at that price straight away
+    ctx.market.place_auction_bid(&item, &second, &900);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, second);
+    assert_eq!(ctx.token.balance(&second), 600);
+    assert_eq!(ctx.token.balance(&seller), 400);
+    assert!(!ctx.market.get_auction(&item).is_active);
+}
+
+#[test]
+fn test_late_bid_extends_auction() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let first = Address::generate(&ctx.env);
+    let second = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &first, 1_000);
+    fund(&ctx, &second, 1_000);
+
+    ctx.market.set_auction_extension(&ctx.admin, &600);
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+
+    // A bid well before the end leaves it alone
+    ctx.market.place_auction_bid(&item, &first, &100);
+    assert_eq!(ctx.market.get_auction(&item).end_time, START_TIME + 3_600);
+
+    // One inside the window pushes the end out to a full window from now
+    advance(&ctx, 3_300);
+    ctx.market.place_auction_bid(&item, &second, &150);
+    assert_eq!(ctx.market.get_auction(&item).end_time, START_TIME + 3_900);
+
+    advance(&ctx, 301);
+    assert_eq!(
+        ctx.market.try_settle_auction(&item),
+        Err(Ok(Error::AuctionStillRunning))
+    );
+    advance(&ctx, 300);
+    ctx.market.settle_auction(&item);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, second);
+}
+
+#[test]
+fn test_auction_extension_is_admin_only() {
+    let ctx = setup_test();
+    let other = Address::generate(&ctx.env);
+
+    assert_eq!(
+        ctx.market.try_set_auction_extension(&other, &600),
+        Err(Ok(Error::InvalidCaller))
+    );
+}
+
+#[test]
+fn test_dutch_price_decays_to_floor() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .start_dutch_auction(&item, &seller, &ctx.token.address, &500, &200, &100, &600);
+    assert_eq!(ctx.market.get_dutch_price(&item), 500);
+
+    advance(&ctx, 1_199);
+    assert_eq!(ctx.market.get_dutch_price(&item), 400);
+
+    // It never drops below the floor
+    advance(&ctx, 6_000);
+    assert_eq!(ctx.market.get_dutch_price(&item), 200);
+
+    ctx.market.buy(&item, &buyer);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+    assert_eq!(ctx.token.balance(&buyer), 800);
+    assert_eq!(ctx.token.balance(&seller), 200);
+    assert_eq!(
+        ctx.market.try_get_dutch_price(&item),
+        Err(Ok(Error::AuctionNotFound))
+    );
+}
+
+#[test]
+fn test_cancel_dutch_auction() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let other = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+
+    let result = ctx.market.try_start_dutch_auction(
+        &item,
+        &seller,
+        &ctx.token.address,
+        &200,
+        &200,
+        &10,
+        &600,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    ctx.market
+        .start_dutch_auction(&item, &seller, &ctx.token.address, &500, &200, &100, &600);
+    assert_eq!(
+        ctx.market.try_cancel_dutch_auction(&item, &other),
+        Err(Ok(Error::InvalidCaller))
+    );
+
+    ctx.market.cancel_dutch_auction(&item, &seller);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+}
+
+#[test]
+fn test_sealed_auction_losers_claim() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let winner = Address::generate(&ctx.env);
+    let loser = Address::generate(&ctx.env);
+    let silent = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    for bidder in [&winner, &loser, &silent] {
+        fund(&ctx, bidder, 1_000);
+    }
+
+    let commitment = |bid: i128| -> BytesN<32> {
+        let mut preimage = Bytes::from_array(&ctx.env, &bid.to_be_bytes());
+        preimage.append(&Bytes::from_slice(&ctx.env, b"salt"));
+        ctx.env.crypto().sha256(&preimage).into()
+    };
+
+    let config = SealedAuctionConfig {
+        token: ctx.token.address.clone(),
+        reserve_price: 0,
+        deposit: 50,
+        commit_duration: 100,
+        reveal_duration: 100,
+        pricing: SealedPricing::FirstPrice,
+    };
+    ctx.market.start_sealed_auction(&item, &seller, &config);
+    ctx.market
+        .commit_sealed_bid(&item, &winner, &commitment(300));
+    ctx.market
+        .commit_sealed_bid(&item, &loser, &commitment(200));
+    ctx.market
+        .commit_sealed_bid(&item, &silent, &commitment(400));
+
+    advance(&ctx, 100);
+    let salt = Bytes::from_slice(&ctx.env, b"salt");
+    ctx.market.reveal_sealed_bid(&item, &winner, &300, &salt);
+    ctx.market.reveal_sealed_bid(&item, &loser, &200, &salt);
+
+    advance(&ctx, 100);
+    ctx.market.finalize_sealed_auction(&item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, winner);
+    assert_eq!(ctx.token.balance(&seller), 300);
+
+    // The item can't be auctioned again until the other bids are settled
+    assert_eq!(
+        ctx.market.try_start_sealed_auction(&item, &winner, &config),
+        Err(Ok(Error::BidsNotClaimed))
+    );
+
+    // Everyone else is settled one claim at a time
+    ctx.market.claim_sealed_bid(&item, &loser);
+    ctx.market.claim_sealed_bid(&item, &silent);
+
+    let escrow = ctx.market.get_escrow_balance(&loser, &ctx.token.address);
+    assert_eq!(escrow.available, 200);
+    assert_eq!(escrow.locked, 0);
+    assert_eq!(ctx.token.balance(&seller), 350);
+    assert_eq!(
+        ctx.market.try_claim_sealed_bid(&item, &loser),
+        Err(Ok(Error::AuctionNotFound))
+    );
+}
+
+#[test]
+fn test_fee_and_royalty_split() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let treasury = Address::generate(&ctx.env);
+    let creator = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market.set_platform_fee(&ctx.admin, &250, &treasury);
+    ctx.market.set_collection_royalty(
+        &ctx.admin,
+        &Bytes::from_slice(&ctx.env, b"weapon"),
+        &creator,
+        &500,
+    );
+
+    ctx.market
+        .list_for_sale(&item, &seller, &1_000, &ctx.token.address, &None);
+    ctx.market.p2p_execute_trade(&item, &buyer);
+
+    assert_eq!(ctx.token.balance(&treasury), 25);
+    assert_eq!(ctx.token.balance(&creator), 50);
+    assert_eq!(ctx.token.balance(&seller), 925);
+
+    let record = ctx.market.get_trade_history(&item).get(0).unwrap();
+    assert_eq!(record.platform_fee, 25);
+    assert_eq!(record.royalty, 50);
+    assert_eq!(record.seller_amount, 925);
+}
+
+#[test]
+fn test_royalty_checked_against_current_fee() {
+    let ctx = setup_test();
+    let treasury = Address::generate(&ctx.env);
+    let creator = Address::generate(&ctx.env);
+    let item = BytesN::from_array(&ctx.env, &[1; 32]);
+
+    ctx.market.set_platform_fee(&ctx.admin, &6_000, &treasury);
+
+    let result = ctx
+        .market
+        .try_set_item_royalty(&ctx.admin, &item, &creator, &5_000);
+    assert_eq!(result, Err(Ok(Error::FeeTooHigh)));
+
+    ctx.market
+        .set_item_royalty(&ctx.admin, &item, &creator, &4_000);
+}
+
+#[test]
+fn test_lowered_royalty_no_longer_caps_fee() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let treasury = Address::generate(&ctx.env);
+    let creator = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .set_item_royalty(&ctx.admin, &item, &creator, &9_000);
+    ctx.market
+        .set_item_royalty(&ctx.admin, &item, &creator, &100);
+    ctx.market.set_platform_fee(&ctx.admin, &5_000, &treasury);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &1_000, &ctx.token.address, &None);
+    ctx.market.p2p_execute_trade(&item, &buyer);
+
+    assert_eq!(ctx.token.balance(&treasury), 500);
+    assert_eq!(ctx.token.balance(&creator), 10);
+    assert_eq!(ctx.token.balance(&seller), 490);
+}
+
+#[test]
+fn test_sale_rejected_when_fee_and_royalty_exceed_price() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let treasury = Address::generate(&ctx.env);
+    let creator = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    // Raising the fee after a royalty is set is allowed, but the sale is refused
+    ctx.market
+        .set_item_royalty(&ctx.admin, &item, &creator, &4_000);
+    ctx.market.set_platform_fee(&ctx.admin, &6_001, &treasury);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &1_000, &ctx.token.address, &None);
+    assert_eq!(
+        ctx.market.try_p2p_execute_trade(&item, &buyer),
+        Err(Ok(Error::FeeTooHigh))
+    );
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, ctx.market_id);
+    assert_eq!(ctx.token.balance(&buyer), 1_000);
+}
+
+#[test]
+fn test_listing_token_allowlist() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+
+    let other_id = ctx
+        .env
+        .register_stellar_asset_contract_v2(ctx.admin.clone())
+        .address();
+    let other = TokenClient::new(&ctx.env, &other_id);
+    StellarAssetClient::new(&ctx.env, &other_id).mint(&buyer, &1_000);
+
+    assert!(!ctx.market.is_payment_token_allowed(&other_id));
+    let result = ctx
+        .market
+        .try_list_for_sale(&item, &seller, &400, &other_id, &None);
+    assert_eq!(result, Err(Ok(Error::TokenNotAllowed)));
+
+    // Each listing is priced and paid in its own token
+    ctx.market.add_payment_token(&ctx.admin, &other_id);
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &other_id, &None);
+    ctx.market.p2p_execute_trade(&item, &buyer);
+    assert_eq!(other.balance(&seller), 400);
+    assert_eq!(ctx.token.balance(&seller), 0);
+
+    ctx.market.remove_payment_token(&ctx.admin, &other_id);
+    assert!(!ctx.market.is_payment_token_allowed(&other_id));
+    assert!(ctx.market.is_payment_token_allowed(&ctx.token.address));
+}
+
+#[test]
+fn test_accept_offer_on_unlisted_item() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .make_offer(&item, &buyer, &300, &ctx.token.address, &(START_TIME + 600));
+
+    // The offer is escrowed until it is settled
+    assert_eq!(ctx.token.balance(&buyer), 700);
+    assert_eq!(ctx.market.get_offers(&item).len(), 1);
+    let result =
+        ctx.market
+            .try_make_offer(&item, &buyer, &350, &ctx.token.address, &(START_TIME + 600));
+    assert_eq!(result, Err(Ok(Error::OfferAlreadyExists)));
+
+    ctx.market.accept_offer(&item, &owner, &buyer);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+    assert_eq!(ctx.token.balance(&owner), 300);
+    assert_eq!(ctx.market.get_offers(&item).len(), 0);
+}
+
+#[test]
+fn test_reject_withdraw_and_expired_offers() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let first = Address::generate(&ctx.env);
+    let second = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+    fund(&ctx, &first, 1_000);
+    fund(&ctx, &second, 1_000);
+
+    ctx.market
+        .make_offer(&item, &first, &300, &ctx.token.address, &(START_TIME + 600));
+    ctx.market.make_offer(
+        &item,
+        &second,
+        &200,
+        &ctx.token.address,
+        &(START_TIME + 600),
+    );
+
+    ctx.market.reject_offer(&item, &owner, &first);
+    assert_eq!(ctx.token.balance(&first), 1_000);
+
+    // An expired offer can't be accepted, but its funds can still be pulled back
+    advance(&ctx, 600);
+    assert_eq!(
+        ctx.market.try_accept_offer(&item, &owner, &second),
+        Err(Ok(Error::OfferExpired))
+    );
+    ctx.market.withdraw_offer(&item, &second);
+    assert_eq!(ctx.token.balance(&second), 1_000);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, owner);
+}
+
+#[test]
+fn test_offer_on_listed_item_needs_delist() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&item, &owner, &500, &ctx.token.address, &None);
+    ctx.market
+        .make_offer(&item, &buyer, &300, &ctx.token.address, &(START_TIME + 600));
+
+    assert_eq!(
+        ctx.market.try_accept_offer(&item, &owner, &buyer),
+        Err(Ok(Error::ItemAlreadyListed))
+    );
+
+    ctx.market.delist_from_sale(&item, &owner);
+    ctx.market.accept_offer(&item, &owner, &buyer);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+}
+
+#[test]
+fn test_bundle_swap() {
+    let ctx = setup_test();
+    let maker = Address::generate(&ctx.env);
+    let taker = Address::generate(&ctx.env);
+    let sword = mint_item(&ctx, &maker, 1);
+    let shield = mint_item(&ctx, &maker, 2);
+    let bow = mint_item(&ctx, &taker, 3);
+    fund(&ctx, &taker, 1_000);
+
+    let mut maker_items = Vec::new(&ctx.env);
+    maker_items.push_back(sword.clone());
+    maker_items.push_back(shield.clone());
+    let mut taker_items = Vec::new(&ctx.env);
+    taker_items.push_back(bow.clone());
+
+    let swap_id = ctx.market.create_swap(
+        &maker,
+        &taker,
+        &maker_items,
+        &taker_items,
+        &ctx.token.address,
+        &0,
+        &100,
+    );
+
+    // The maker's side is held until the swap is accepted or cancelled
+    assert_eq!(ctx.registry.get_asset_info(&sword).owner, ctx.market_id);
+    assert_eq!(
+        ctx.market.try_accept_swap(&swap_id, &maker),
+        Err(Ok(Error::InvalidCaller))
+    );
+
+    ctx.market.accept_swap(&swap_id, &taker);
+
+    assert_eq!(ctx.registry.get_asset_info(&sword).owner, taker);
+    assert_eq!(ctx.registry.get_asset_info(&shield).owner, taker);
+    assert_eq!(ctx.registry.get_asset_info(&bow).owner, maker);
+    assert_eq!(ctx.token.balance(&maker), 100);
+    assert_eq!(
+        ctx.market.try_get_swap(&swap_id),
+        Err(Ok(Error::SwapNotFound))
+    );
+}
+
+#[test]
+fn test_cancel_swap_returns_maker_side() {
+    let ctx = setup_test();
+    let maker = Address::generate(&ctx.env);
+    let taker = Address::generate(&ctx.env);
+    let sword = mint_item(&ctx, &maker, 1);
+    let bow = mint_item(&ctx, &taker, 2);
+    fund(&ctx, &maker, 1_000);
+
+    let mut maker_items = Vec::new(&ctx.env);
+    maker_items.push_back(sword.clone());
+    let mut taker_items = Vec::new(&ctx.env);
+    taker_items.push_back(bow);
+
+    let swap_id = ctx.market.create_swap(
+        &maker,
+        &taker,
+        &maker_items,
+        &taker_items,
+        &ctx.token.address,
+        &250,
+        &0,
+    );
+    assert_eq!(ctx.token.balance(&maker), 750);
+
+    ctx.market.cancel_swap(&swap_id, &maker);
+
+    assert_eq!(ctx.registry.get_asset_info(&sword).owner, maker);
+    assert_eq!(ctx.token.balance(&maker), 1_000);
+}
+
+#[test]
+fn test_expired_listing_is_purged() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let expiring = mint_item(&ctx, &seller, 1);
+    let open = mint_item(&ctx, &seller, 2);
+    fund(&ctx, &buyer, 1_000);
+
+    let result = ctx.market.try_list_for_sale(
+        &expiring,
+        &seller,
+        &400,
+        &ctx.token.address,
+        &Some(START_TIME),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidEndTime)));
+
+    ctx.market.list_for_sale(
+        &expiring,
+        &seller,
+        &400,
+        &ctx.token.address,
+        &Some(START_TIME + 600),
+    );
+    ctx.market
+        .list_for_sale(&open, &seller, &400, &ctx.token.address, &None);
+
+    advance(&ctx, 600);
+    assert_eq!(
+        ctx.market.try_p2p_execute_trade(&expiring, &buyer),
+        Err(Ok(Error::ListingExpired))
+    );
+
+    // Only the expired listing is taken down
+    let mut item_ids = Vec::new(&ctx.env);
+    item_ids.push_back(expiring.clone());
+    item_ids.push_back(open.clone());
+    let purged = ctx.market.purge_expired(&item_ids);
+
+    assert_eq!(purged.len(), 1);
+    assert_eq!(purged.get(0).unwrap(), expiring);
+    assert_eq!(ctx.registry.get_asset_info(&expiring).owner, seller);
+    assert_eq!(ctx.registry.get_asset_info(&open).owner, ctx.market_id);
+}
+
+#[test]
+fn test_migrate_baseline_deployment() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    // Put the instance back into the original layout: the admin, one token and
+    // the empty whole-map keys, with no registry or allowlist
+    ctx.env.as_contract(&ctx.market_id, || {
+        let instance = ctx.env.storage().instance();
+        instance.remove(&DataKey::AssetRegistry);
+        instance.remove(&DataKey::AllowedTokens);
+        instance.set(&LegacyDataKey::TokenAddress, &ctx.token.address);
+        instance.set(&LegacyDataKey::Items, &Map::<Bytes, bool>::new(&ctx.env));
+        instance.set(&LegacyDataKey::Escrow, &Map::<Address, bool>::new(&ctx.env));
+    });
+
+    assert!(ctx
+        .market
+        .try_list_for_sale(&item, &seller, &400, &ctx.token.address, &None)
+        .is_err());
+    assert_eq!(
+        ctx.market
+            .try_initialize(&ctx.admin, &ctx.token.address, &ctx.registry.address),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    ctx.market
+        .migrate_storage(&ctx.admin, &ctx.registry.address);
+
+    assert!(ctx.market.is_payment_token_allowed(&ctx.token.address));
+    ctx.env.as_contract(&ctx.market_id, || {
+        let instance = ctx.env.storage().instance();
+        assert!(!instance.has(&LegacyDataKey::TokenAddress));
+        assert!(!instance.has(&LegacyDataKey::Items));
+        assert!(!instance.has(&LegacyDataKey::Escrow));
+    });
+
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &ctx.token.address, &None);
+    ctx.market.p2p_execute_trade(&item, &buyer);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+    assert_eq!(ctx.token.balance(&seller), 400);
+
+    // The registry is set once
+    ctx.market
+        .migrate_storage(&ctx.admin, &ctx.registry.address);
+    let other = Address::generate(&ctx.env);
+    assert_eq!(
+        ctx.market.try_migrate_storage(&ctx.admin, &other),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_end_time_page_from_zero_cursor() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let first = mint_item(&ctx, &seller, 1);
+    let second = mint_item(&ctx, &seller, 2);
+
+    ctx.market.start_auction(
+        &first,
+        &seller,
+        &50,
+        &7_200,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+    ctx.market.start_auction(
+        &second,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+
+    let page = ctx
+        .market
+        .get_active_auctions(&AuctionSort::EndTime, &0, &10);
+    assert_eq!(page.auctions.len(), 2);
+    assert_eq!(page.auctions.get(0).unwrap().item_id, second);
+    assert_eq!(page.auctions.get(1).unwrap().item_id, first);
+}
+
+#[test]
+fn test_listing_pages_skip_expired_offers() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let expiring = mint_item(&ctx, &seller, 1);
+    let open = mint_item(&ctx, &seller, 2);
+
+    ctx.market.list_for_sale(
+        &expiring,
+        &seller,
+        &400,
+        &ctx.token.address,
+        &Some(START_TIME + 600),
+    );
+    ctx.market
+        .list_for_sale(&open, &seller, &400, &ctx.token.address, &None);
+    assert_eq!(ctx.market.get_active_listings(&0, &10).offers.len(), 2);
+
+    advance(&ctx, 600);
+
+    let page = ctx.market.get_active_listings(&0, &10);
+    assert_eq!(page.offers.len(), 1);
+    assert_eq!(page.offers.get(0).unwrap().item_id, open);
+
+    let page = ctx.market.get_listings_by_seller(&seller, &0, &10);
+    assert_eq!(page.offers.len(), 1);
+    assert_eq!(page.offers.get(0).unwrap().item_id, open);
+}
+
+#[test]
+fn test_pause_halts_trading_but_not_delisting() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let listed = mint_item(&ctx, &seller, 1);
+    let unlisted = mint_item(&ctx, &seller, 2);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&listed, &seller, &400, &ctx.token.address, &None);
+
+    let outsider = Address::generate(&ctx.env);
+    assert_eq!(
+        ctx.market.try_pause(&outsider),
+        Err(Ok(Error::InvalidCaller))
+    );
+
+    ctx.market.pause(&ctx.admin);
+    assert!(ctx.market.is_paused());
+    assert_eq!(
+        ctx.market
+            .try_list_for_sale(&unlisted, &seller, &400, &ctx.token.address, &None),
+        Err(Ok(Error::ContractPaused))
+    );
+    assert_eq!(
+        ctx.market.try_p2p_execute_trade(&listed, &buyer),
+        Err(Ok(Error::ContractPaused))
+    );
+
+    // Sellers can still take their items back
+    ctx.market.delist_from_sale(&listed, &seller);
+    assert_eq!(ctx.registry.get_asset_info(&listed).owner, seller);
+
+    ctx.market.unpause(&ctx.admin);
+    assert!(!ctx.market.is_paused());
+    ctx.market
+        .list_for_sale(&unlisted, &seller, &400, &ctx.token.address, &None);
+    ctx.market.p2p_execute_trade(&unlisted, &buyer);
+    assert_eq!(ctx.registry.get_asset_info(&unlisted).owner, buyer);
+}
+
+#[test]
+fn test_blocked_address_cannot_trade() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &ctx.token.address, &None);
+
+    ctx.market.block_address(&ctx.admin, &buyer);
+    assert!(ctx.market.is_blocked(&buyer));
+    assert_eq!(
+        ctx.market.try_p2p_execute_trade(&item, &buyer),
+        Err(Ok(Error::AddressBlocked))
+    );
+
+    // A blocked seller's listing can't be bought either
+    ctx.market.unblock_address(&ctx.admin, &buyer);
+    ctx.market.block_address(&ctx.admin, &seller);
+    assert_eq!(
+        ctx.market.try_p2p_execute_trade(&item, &buyer),
+        Err(Ok(Error::AddressBlocked))
+    );
+
+    ctx.market.unblock_address(&ctx.admin, &seller);
+    assert!(!ctx.market.is_blocked(&seller));
+    ctx.market.p2p_execute_trade(&item, &buyer);
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+}
+
+#[test]
+fn test_force_delist_listing_refunds_offers() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&item, &seller, &400, &ctx.token.address, &None);
+    ctx.market
+        .make_offer(&item, &buyer, &300, &ctx.token.address, &(START_TIME + 600));
+    assert_eq!(ctx.token.balance(&buyer), 700);
+
+    let outsider = Address::generate(&ctx.env);
+    assert_eq!(
+        ctx.market.try_force_delist(&outsider, &item),
+        Err(Ok(Error::InvalidCaller))
+    );
+
+    ctx.market.force_delist(&ctx.admin, &item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+    assert_eq!(ctx.market.get_active_listings(&0, &10).offers.len(), 0);
+    assert_eq!(ctx.market.get_offers(&item).len(), 0);
+    assert_eq!(ctx.token.balance(&buyer), 1_000);
+    assert_eq!(
+        ctx.market.try_force_delist(&ctx.admin, &item),
+        Err(Ok(Error::ItemNotListed))
+    );
+}
+
+#[test]
+fn test_force_delist_auction_refunds_bid() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let bidder = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &bidder, 1_000);
+
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+    ctx.market.place_auction_bid(&item, &bidder, &100);
+    assert_eq!(ctx.token.balance(&bidder), 900);
+
+    ctx.market.force_delist(&ctx.admin, &item);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, seller);
+    assert_eq!(ctx.token.balance(&bidder), 1_000);
+    let escrow = ctx.market.get_escrow_balance(&bidder, &ctx.token.address);
+    assert_eq!(escrow.locked, 0);
+}
+
+#[test]
+fn test_force_delist_swap_returns_maker_side() {
+    let ctx = setup_test();
+    let maker = Address::generate(&ctx.env);
+    let taker = Address::generate(&ctx.env);
+    let sword = mint_item(&ctx, &maker, 1);
+    let shield = mint_item(&ctx, &maker, 2);
+    let bow = mint_item(&ctx, &taker, 3);
+    fund(&ctx, &maker, 1_000);
+
+    let mut maker_items = Vec::new(&ctx.env);
+    maker_items.push_back(sword.clone());
+    maker_items.push_back(shield.clone());
+    let mut taker_items = Vec::new(&ctx.env);
+    taker_items.push_back(bow);
+
+    let swap_id = ctx.market.create_swap(
+        &maker,
+        &taker,
+        &maker_items,
+        &taker_items,
+        &ctx.token.address,
+        &250,
+        &0,
+    );
+
+    // Pulling one item calls off the whole swap
+    ctx.market.force_delist(&ctx.admin, &sword);
+
+    assert_eq!(ctx.registry.get_asset_info(&sword).owner, maker);
+    assert_eq!(ctx.registry.get_asset_info(&shield).owner, maker);
+    assert_eq!(ctx.token.balance(&maker), 1_000);
+    assert_eq!(
+        ctx.market.try_get_swap(&swap_id),
+        Err(Ok(Error::SwapNotFound))
+    );
+    assert_eq!(
+        ctx.market.try_force_delist(&ctx.admin, &shield),
+        Err(Ok(Error::ItemNotListed))
+    );
+
+    // The items can be listed again
+    ctx.market
+        .list_for_sale(&shield, &maker, &400, &ctx.token.address, &None);
+}
+
+#[test]
+fn test_deposit_and_withdraw_available_balance() {
+    let ctx = setup_test();
+    let user = Address::generate(&ctx.env);
+    fund(&ctx, &user, 1_000);
+
+    assert_eq!(
+        ctx.market.try_deposit(&user, &ctx.token.address, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+
+    ctx.market.deposit(&user, &ctx.token.address, &600);
+    assert_eq!(ctx.token.balance(&user), 400);
+    let escrow = ctx.market.get_escrow_balance(&user, &ctx.token.address);
+    assert_eq!(escrow.available, 600);
+    assert_eq!(escrow.locked, 0);
+
+    assert_eq!(
+        ctx.market.try_withdraw(&user, &ctx.token.address, &601),
+        Err(Ok(Error::InsufficientFunds))
+    );
+
+    ctx.market.withdraw(&user, &ctx.token.address, &250);
+    assert_eq!(ctx.token.balance(&user), 650);
+    let escrow = ctx.market.get_escrow_balance(&user, &ctx.token.address);
+    assert_eq!(escrow.available, 350);
+}
+
+#[test]
+fn test_withdraw_only_unlocked_funds() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let bidder = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &bidder, 1_000);
+
+    ctx.market.deposit(&bidder, &ctx.token.address, &500);
+    ctx.market.start_auction(
+        &item,
+        &seller,
+        &50,
+        &3_600,
+        &plain_auction(),
+        &ctx.token.address,
+    );
+    ctx.market.place_auction_bid(&item, &bidder, &300);
+
+    // The bid is paid out of the deposit and stays locked behind it
+    assert_eq!(ctx.token.balance(&bidder), 500);
+    let escrow = ctx.market.get_escrow_balance(&bidder, &ctx.token.address);
+    assert_eq!(escrow.available, 200);
+    assert_eq!(escrow.locked, 300);
+    assert_eq!(
+        ctx.market.try_withdraw(&bidder, &ctx.token.address, &300),
+        Err(Ok(Error::InsufficientFunds))
+    );
+
+    // Withdrawals keep working while the contract is paused
+    ctx.market.pause(&ctx.admin);
+    ctx.market.withdraw(&bidder, &ctx.token.address, &200);
+    assert_eq!(ctx.token.balance(&bidder), 700);
+}
+
+#[test]
+fn test_collection_floor_and_sales_stats() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let weapon = Bytes::from_slice(&ctx.env, b"weapon");
+    fund(&ctx, &buyer, 1_000);
+
+    let prices = [500, 300, 400, 200, 600, 350];
+    let mut items = Vec::new(&ctx.env);
+    for (n, price) in prices.iter().enumerate() {
+        let item = mint_item(&ctx, &seller, n as u8 + 1);
+        // The cheapest listing runs out first
+        let expires_at = if *price == 200 {
+            Some(START_TIME + 600)
+        } else {
+            None
+        };
+        ctx.market
+            .list_for_sale(&item, &seller, price, &ctx.token.address, &expires_at);
+        items.push_back(item);
+    }
+
+    let floor = |ctx: &TestContext| {
+        ctx.market
+            .get_collection_stats(&weapon, &ctx.token.address)
+            .floor_price
+    };
+    assert_eq!(floor(&ctx), Some(200));
+
+    // Expired listings drop out of the floor before they are purged
+    advance(&ctx, 600);
+    assert_eq!(floor(&ctx), Some(300));
+
+    ctx.market.p2p_execute_trade(&items.get(1).unwrap(), &buyer);
+    assert_eq!(floor(&ctx), Some(350));
+
+    ctx.market.delist_from_sale(&items.get(5).unwrap(), &seller);
+    assert_eq!(floor(&ctx), Some(400));
+
+    ctx.market.purge_expired(&items);
+    assert_eq!(floor(&ctx), Some(400));
+
+    let stats = ctx.market.get_collection_stats(&weapon, &ctx.token.address);
+    assert_eq!(stats.last_sale_price, 300);
+    assert_eq!(stats.trade_count, 1);
+    assert_eq!(stats.volume_24h, 300);
+    assert_eq!(stats.volume_7d, 300);
+
+    // Sales roll out of the 24h window but stay in the 7d one
+    advance(&ctx, 2 * 86_400);
+    let stats = ctx.market.get_collection_stats(&weapon, &ctx.token.address);
+    assert_eq!(stats.volume_24h, 0);
+    assert_eq!(stats.volume_7d, 300);
+
+    for item in [0, 2, 4] {
+        ctx.market
+            .delist_from_sale(&items.get(item).unwrap(), &seller);
+    }
+    assert_eq!(floor(&ctx), None);
+}
+
+#[test]
+fn test_rental_keeps_custody_and_expires() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let renter = Address::generate(&ctx.env);
+    let next_renter = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+    fund(&ctx, &renter, 1_000);
+    fund(&ctx, &next_renter, 1_000);
+
+    let terms = RentalTerms {
+        token: ctx.token.address.clone(),
+        price_per_period: 10,
+        period: 3_600,
+        max_periods: 24,
+        collateral: 200,
+    };
+    ctx.market.list_for_rent(&item, &owner, &terms);
+    ctx.market.rent_item(&item, &renter, &2);
+
+    // Usage rights move, ownership doesn't
+    assert_eq!(ctx.market.get_renter(&item), Some(renter.clone()));
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, ctx.market_id);
+    assert_eq!(ctx.token.balance(&owner), 20);
+    assert_eq!(
+        ctx.market.try_cancel_rental(&item, &owner),
+        Err(Ok(Error::ItemRented))
+    );
+
+    // The rental ends on its own
+    advance(&ctx, 7_200);
+    assert_eq!(ctx.market.get_renter(&item), None);
+
+    // and the next rental hands the previous collateral back to escrow
+    ctx.market.rent_item(&item, &next_renter, &1);
+    assert_eq!(ctx.market.get_renter(&item), Some(next_renter));
+    let escrow = ctx.market.get_escrow_balance(&renter, &ctx.token.address);
+    assert_eq!(escrow.available, 200);
+    assert_eq!(escrow.locked, 0);
+}
+
+#[test]
+fn test_rental_requires_collateral() {
+    let ctx = setup_test();
+    let owner = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &owner, 1);
+
+    let terms = RentalTerms {
+        token: ctx.token.address.clone(),
+        price_per_period: 10,
+        period: 3_600,
+        max_periods: 24,
+        collateral: 0,
+    };
+    let result = ctx.market.try_list_for_rent(&item, &owner, &terms);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_fill_order_pulls_approved_item() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let item = mint_item(&ctx, &seller, 1);
+    fund(&ctx, &buyer, 1_000);
+
+    let key = SigningKey::from_bytes(&[7; 32]);
+    ctx.market.set_order_signer(
+        &seller,
+        &BytesN::from_array(&ctx.env, &key.verifying_key().to_bytes()),
+    );
+
+    let order = Order {
+        item_id: item.clone(),
+        seller: seller.clone(),
+        token: ctx.token.address.clone(),
+        price: 250,
+        nonce: 1,
+        expires_at: START_TIME + 3_600,
+    };
+    let payload: std::vec::Vec<u8> = ctx.market.order_payload(&order).iter().collect();
+    let signature = BytesN::from_array(&ctx.env, &key.sign(&payload).to_bytes());
+
+    // Without an approval the marketplace can't move the item
+    assert!(ctx
+        .market
+        .try_fill_order(&order, &signature, &buyer)
+        .is_err());
+
+    ctx.registry
+        .set_approval_for_all(&seller, &ctx.market_id, &true);
+    ctx.market.fill_order(&order, &signature, &buyer);
+
+    assert_eq!(ctx.registry.get_asset_info(&item).owner, buyer);
+    assert_eq!(ctx.token.balance(&seller), 250);
+    assert!(ctx.market.is_order_nonce_used(&seller, &1));
+}
+
+#[test]
+fn test_batch_list_and_buy() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    fund(&ctx, &buyer, 1_000);
+
+    let mut listings = Vec::new(&ctx.env);
+    let mut item_ids = Vec::new(&ctx.env);
+    for n in 1..=3 {
+        let item_id = mint_item(&ctx, &seller, n);
+        listings.push_back(BatchListing {
+            item_id: item_id.clone(),
+            price: 100 * n as i128,
+        });
+        item_ids.push_back(item_id);
+    }
+
+    let offers = ctx
+        .market
+        .batch_list_for_sale(&listings, &seller, &ctx.token.address, &None);
+    assert_eq!(offers.len(), 3);
+    assert_eq!(offers.get(2).unwrap().price, 300);
+
+    ctx.market.batch_execute_trade(&item_ids, &buyer);
+
+    for item_id in item_ids.iter() {
+        assert_eq!(ctx.registry.get_asset_info(&item_id).owner, buyer);
+    }
+    assert_eq!(ctx.token.balance(&buyer), 400);
+    assert_eq!(ctx.token.balance(&seller), 600);
+}
+
+#[test]
+fn test_batch_buy_is_all_or_nothing() {
+    let ctx = setup_test();
+    let seller = Address::generate(&ctx.env);
+    let buyer = Address::generate(&ctx.env);
+    let first = mint_item(&ctx, &seller, 1);
+    let second = mint_item(&ctx, &seller, 2);
+    let unlisted = mint_item(&ctx, &seller, 3);
+    fund(&ctx, &buyer, 1_000);
+
+    ctx.market
+        .list_for_sale(&first, &seller, &100, &ctx.token.address, &None);
+    ctx.market
+        .list_for_sale(&second, &seller, &200, &ctx.token.address, &None);
+
+    // A repeated id would be paid for twice
+    let mut item_ids = Vec::new(&ctx.env);
+    item_ids.push_back(first.clone());
+    item_ids.push_back(first.clone());
+    assert_eq!(
+        ctx.market.try_batch_execute_trade(&item_ids, &buyer),
+        Err(Ok(Error::DuplicateItem))
+    );
+
+    // One bad id reverts the whole basket
+    let mut item_ids = Vec::new(&ctx.env);
+    item_ids.push_back(first.clone());
+    item_ids.push_back(second.clone());
+    item_ids.push_back(unlisted);
+    assert!(ctx
+        .market
+        .try_batch_execute_trade(&item_ids, &buyer)
+        .is_err());
+    assert_eq!(ctx.registry.get_asset_info(&first).owner, ctx.market_id);
+    assert_eq!(ctx.token.balance(&buyer), 1_000);
+
+    assert_eq!(
+        ctx.market
+            .try_batch_execute_trade(&Vec::new(&ctx.env), &buyer),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
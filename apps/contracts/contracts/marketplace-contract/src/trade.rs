@@ -1,219 +1,380 @@
-use soroban_sdk::{Address, Env, Map, String, Symbol, Vec};
-use crate::escrow::{deposit_escrow, withdraw_escrow};
-use crate::utils::{DataKey, Error, GameItem, TradeHistory, TradeOffer};
+use crate::admin::internal_check_can_trade;
+use crate::escrow::{deposit_escrow, internal_check_token};
+use crate::fees::pay_out_sale;
+use crate::history::record_trade;
+use crate::index::{index_add, index_page, index_remove};
+use crate::registry::{release_custody, take_custody};
+use crate::stats::{add_listing_price, remove_listing_price};
+use crate::storage::{get_trade_offer, is_listed, remove_trade_offer, set_listed, set_trade_offer};
+use crate::utils::{BatchListing, Error, IndexKind, ListingPage, TradeOffer};
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec};
 
 pub fn create_trade_offer(
     e: Env,
-    item_id: String,
+    item_id: BytesN<32>,
     seller: Address,
     price: i128,
+    token: Address,
+    expires_at: Option<u64>,
 ) -> Result<(), Error> {
     seller.require_auth();
 
-    if price <= 0 {
+    internal_check_can_trade(&e, &seller)?;
+
+    internal_create_offer(&e, item_id, seller, price, token, expires_at)?;
+
+    Ok(())
+}
+
+pub const MAX_BATCH_SIZE: u32 = 20;
+
+// List several items at one token and expiry; if any listing fails none are created
+pub fn batch_create_trade_offers(
+    e: Env,
+    listings: Vec<BatchListing>,
+    seller: Address,
+    token: Address,
+    expires_at: Option<u64>,
+) -> Result<Vec<TradeOffer>, Error> {
+    seller.require_auth();
+
+    internal_check_can_trade(&e, &seller)?;
+
+    if listings.is_empty() || listings.len() > MAX_BATCH_SIZE {
         return Err(Error::InvalidAmount);
     }
 
-    let items_key = DataKey::Items;
-    let trade_offer_key = DataKey::TradeOffer;
-    let listed_key = DataKey::Listed;
+    let mut offers = Vec::new(&e);
+    for listing in listings.iter() {
+        offers.push_back(internal_create_offer(
+            &e,
+            listing.item_id,
+            seller.clone(),
+            listing.price,
+            token.clone(),
+            expires_at,
+        )?);
+    }
 
-    let items: Map<String, GameItem> = match e.storage().instance().get(&items_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+    Ok(offers)
+}
 
-    let mut trade_offer: Map<String, TradeOffer> = match e.storage().instance().get(&trade_offer_key)
-    {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+fn internal_create_offer(
+    e: &Env,
+    item_id: BytesN<32>,
+    seller: Address,
+    price: i128,
+    token: Address,
+    expires_at: Option<u64>,
+) -> Result<TradeOffer, Error> {
+    if price <= 0 {
+        return Err(Error::InvalidAmount);
+    }
+    if let Some(expiry) = expires_at {
+        if expiry <= e.ledger().timestamp() {
+            return Err(Error::InvalidEndTime);
+        }
+    }
 
-    let mut listed: Map<String, bool> = match e.storage().instance().get(&listed_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+    internal_check_token(e, &token)?;
 
-    if items.get(item_id.clone()).unwrap().owner != seller {
-        return Err(Error::NotItemOwner);
-    }
-    if listed.get(item_id.clone()).unwrap() {
+    if is_listed(e, &item_id) {
         return Err(Error::ItemAlreadyListed);
     }
 
-    if trade_offer.get(item_id.clone()).unwrap().is_active {
-        return Err(Error::ItemAlreadyListed);
+    if let Some(existing_offer) = get_trade_offer(e, &item_id) {
+        if existing_offer.is_active {
+            return Err(Error::ItemAlreadyListed);
+        }
     }
 
+    // Hold the item in the marketplace until the offer is filled or cancelled
+    take_custody(e, &item_id, &seller)?;
+
     let instance_trade_offer = TradeOffer {
         item_id: item_id.clone(),
         seller,
         price,
+        token,
+        expires_at,
         is_active: true,
     };
 
-    trade_offer.set(item_id.clone(), instance_trade_offer.clone());
-    listed.set(item_id.clone(), true);
-
-    e.storage().instance().set(&trade_offer_key, &trade_offer);
-    e.storage().instance().set(&listed_key, &listed);
+    set_trade_offer(e, &instance_trade_offer);
+    set_listed(e, &item_id, true);
+    internal_index_offer(e, &instance_trade_offer)?;
 
     e.events().publish(
-        (Symbol::new(&e, "created_trade_offer"), item_id),
-        instance_trade_offer,
+        (Symbol::new(e, "created_trade_offer"), item_id),
+        instance_trade_offer.clone(),
     );
 
-    Ok(())
+    Ok(instance_trade_offer)
 }
 
-pub fn execute_trade(e: Env, item_id: String, buyer: Address) -> Result<(), Error> {
+pub fn execute_trade(e: Env, item_id: BytesN<32>, buyer: Address) -> Result<(), Error> {
     buyer.require_auth();
 
-    let items_key = DataKey::Items;
-    let trade_offer_key = DataKey::TradeOffer;
-    let listed_key = DataKey::Listed;
-    let trade_history_key = DataKey::TradeHistory;
+    internal_check_can_trade(&e, &buyer)?;
 
-    let mut histories: Map<String, Vec<TradeHistory>> =
-        match e.storage().instance().get(&trade_history_key) {
-            Some(x) => x,
-            None => Map::new(&e),
-        };
+    let offer = internal_get_open_offer(&e, &item_id, &buyer)?;
 
-    let mut items: Map<String, GameItem> = match e.storage().instance().get(&items_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+    //  Deposit buyer's payment into escrow
+    deposit_escrow(&e, buyer.clone(), offer.token.clone(), offer.price)?;
 
-    let mut trade_offer: Map<String, TradeOffer> =
-        match e.storage().instance().get(&trade_offer_key) {
-            Some(x) => x,
-            None => Map::new(&e),
-        };
+    internal_complete_sale(&e, offer, &buyer)
+}
 
-    let mut listed: Map<String, bool> = match e.storage().instance().get(&listed_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+// Buy several listings at once. They must share a payment token so the buyer pays in
+// a single transfer; any failure reverts the whole batch
+pub fn batch_execute_trade(e: Env, item_ids: Vec<BytesN<32>>, buyer: Address) -> Result<(), Error> {
+    buyer.require_auth();
 
-    let mut item = match items.get(item_id.clone()) {
-        Some(x) => x,
-        None => return Err(Error::NotInitialized),
-    };
+    internal_check_can_trade(&e, &buyer)?;
+
+    if item_ids.is_empty() || item_ids.len() > MAX_BATCH_SIZE {
+        return Err(Error::InvalidAmount);
+    }
+
+    let mut offers: Vec<TradeOffer> = Vec::new(&e);
+    let mut total = 0;
+    for item_id in item_ids.iter() {
+        // Repeated ids would be paid for twice
+        if offers.iter().any(|x| x.item_id == item_id) {
+            return Err(Error::DuplicateItem);
+        }
+
+        let offer = internal_get_open_offer(&e, &item_id, &buyer)?;
 
-    let offer = match trade_offer.get(item_id.clone()) {
+        if let Some(first) = offers.first() {
+            if first.token != offer.token {
+                return Err(Error::MixedPaymentTokens);
+            }
+        }
+
+        total += offer.price;
+        offers.push_back(offer);
+    }
+
+    //  Deposit the whole basket in one transfer
+    let token = offers.get_unchecked(0).token;
+    deposit_escrow(&e, buyer.clone(), token, total)?;
+
+    for offer in offers.iter() {
+        internal_complete_sale(&e, offer, &buyer)?;
+    }
+
+    Ok(())
+}
+
+fn internal_get_open_offer(
+    e: &Env,
+    item_id: &BytesN<32>,
+    buyer: &Address,
+) -> Result<TradeOffer, Error> {
+    let offer = match get_trade_offer(e, item_id) {
         Some(x) => x,
-        None => return Err(Error::NotInitialized),
+        None => return Err(Error::TradeOfferNotFound),
     };
 
-    if !listed.get(item_id.clone()).unwrap_or(false) {
-        return Err(Error::NotInitialized);
+    if !is_listed(e, item_id) {
+        return Err(Error::ItemNotListed);
     }
-    if offer.seller == buyer {
+    if offer.seller == *buyer {
         return Err(Error::InvalidCaller);
     }
     if !offer.is_active {
         return Err(Error::OfferNotActive);
     }
+    if internal_is_expired(e, &offer) {
+        return Err(Error::ListingExpired);
+    }
+    internal_check_can_trade(e, &offer.seller)?;
 
-    //  Deposit buyer's payment into escrow
-    deposit_escrow(&e, buyer.clone(), offer.price)?;
+    Ok(offer)
+}
+
+// Hand over an item whose price the buyer already has in escrow
+fn internal_complete_sale(e: &Env, offer: TradeOffer, buyer: &Address) -> Result<(), Error> {
+    let item_id = offer.item_id.clone();
 
     //  Transfer item to buyer
-    item.owner = buyer.clone();
-    items.set(item_id.clone(), item.clone());
-    e.storage().instance().set(&items_key, &items);
+    release_custody(e, &item_id, buyer, b"marketplace_sale")?;
 
     //  Remove the trade offer and listing
-    trade_offer.remove(item_id.clone());
-    e.storage().instance().set(&trade_offer_key, &trade_offer);
+    remove_trade_offer(e, &item_id);
+    set_listed(e, &item_id, false);
+    internal_unindex_offer(e, &offer)?;
+
+    //  Pay the seller, less platform fee and royalty
+    let split = pay_out_sale(
+        e,
+        &item_id,
+        buyer.clone(),
+        offer.seller.clone(),
+        offer.token.clone(),
+        offer.price,
+    )?;
 
-    listed.set(item_id.clone(), false);
-    e.storage().instance().set(&listed_key, &listed);
-
-    //  Transfer escrowed funds to seller
-    withdraw_escrow(&e, buyer.clone(), offer.price)?;
-    deposit_escrow(&e, offer.seller.clone(), offer.price)?;
+    // EVENTS
+    e.events().publish(
+        (Symbol::new(e, "trade_executed"), buyer.clone()),
+        item_id.clone(),
+    );
 
     // TRADE HISTORY
-    let history_entry = TradeHistory {
-        seller: offer.seller.clone(),
-        buyer: buyer.clone(),
-        price: offer.price,
-        timestamp: e.ledger().timestamp(),
+    record_trade(
+        e,
+        item_id,
+        offer.seller,
+        buyer.clone(),
+        offer.token,
+        offer.price,
+        &split,
+    )
+}
+
+pub fn cancel_trade_offer(e: Env, item_id: BytesN<32>, seller: Address) -> Result<(), Error> {
+    seller.require_auth();
+
+    let offer = match get_trade_offer(&e, &item_id) {
+        Some(x) => x,
+        None => return Err(Error::TradeOfferNotFound),
     };
 
-    let mut item_histories = histories.get(item_id.clone()).unwrap_or(Vec::new(&e));
-    item_histories.push_back(history_entry);
-    histories.set(item_id.clone(), item_histories);
+    if offer.seller != seller {
+        return Err(Error::NotItemOwner);
+    }
 
-    e.storage().instance().set(&trade_history_key, &histories);
+    if !is_listed(&e, &item_id) {
+        return Err(Error::ItemNotListed);
+    }
 
-    // EVENTS
-    e.events()
-        .publish((Symbol::new(&e, "trade_executed"), buyer.clone()), item);
+    if !offer.is_active {
+        return Err(Error::OfferNotActive);
+    }
 
-    e.events().publish(
-        (Symbol::new(&e, "Trade_History_Updated"), item_id.clone()),
-        (offer.seller, buyer, offer.price, e.ledger().timestamp()),
-    );
+    internal_return_to_seller(&e, &offer, b"marketplace_delisting")?;
+
+    e.events()
+        .publish((Symbol::new(&e, "canceled_trade_offer"), seller), item_id);
 
     Ok(())
 }
 
-pub fn cancel_trade_offer(e: Env, item_id: String, seller: Address) -> Result<(), Error> {
-    seller.require_auth();
+pub const MAX_PURGE_BATCH: u32 = 50;
 
-    let items_key = DataKey::Items;
-    let trade_offer_key = DataKey::TradeOffer;
-    let listed_key = DataKey::Listed;
+// Delist whichever of `item_ids` have expired, returning the items to their sellers.
+// Anyone can call this; returns the ids that were purged
+pub fn purge_expired(e: Env, item_ids: Vec<BytesN<32>>) -> Result<Vec<BytesN<32>>, Error> {
+    if item_ids.len() > MAX_PURGE_BATCH {
+        return Err(Error::InvalidAmount);
+    }
 
-    let items: Map<String, GameItem> = match e.storage().instance().get(&items_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
+    let mut purged: Vec<BytesN<32>> = Vec::new(&e);
 
-    let mut trade_offer: Map<String, TradeOffer> =
-        match e.storage().instance().get(&trade_offer_key) {
+    for item_id in item_ids.iter() {
+        let offer = match get_trade_offer(&e, &item_id) {
             Some(x) => x,
-            None => Map::new(&e),
+            None => continue,
         };
+        if !internal_is_expired(&e, &offer) {
+            continue;
+        }
 
-    let mut listed: Map<String, bool> = match e.storage().instance().get(&listed_key) {
-        Some(x) => x,
-        None => Map::new(&e),
-    };
-
-    let item = match items.get(item_id.clone()) {
-        Some(x) => x,
-        None => return Err(Error::NotInitialized),
-    };
+        internal_return_to_seller(&e, &offer, b"marketplace_listing_expired")?;
 
-    let offer = match trade_offer.get(item_id.clone()) {
-        Some(x) => x,
-        None => return Err(Error::NotInitialized),
-    };
+        e.events().publish(
+            (Symbol::new(&e, "listing_expired"), offer.seller),
+            item_id.clone(),
+        );
 
-    if item.owner != seller {
-        return Err(Error::NotItemOwner);
+        purged.push_back(item_id);
     }
 
-    if !listed.get(item_id.clone()).unwrap_or(false) {
-        return Err(Error::ItemNotListed);
-    }
+    Ok(purged)
+}
 
-    if !offer.is_active {
-        return Err(Error::OfferNotActive);
-    }
+pub fn internal_force_delist(e: &Env, offer: TradeOffer) -> Result<Address, Error> {
+    internal_return_to_seller(e, &offer, b"marketplace_force_delisted")?;
+
+    Ok(offer.seller)
+}
 
-    trade_offer.remove(item_id.clone());
-    listed.set(item_id.clone(), false);
+pub fn get_trade_offer_by_id(e: Env, item_id: BytesN<32>) -> Result<TradeOffer, Error> {
+    get_trade_offer(&e, &item_id).ok_or(Error::TradeOfferNotFound)
+}
 
-    e.storage().instance().set(&trade_offer_key, &trade_offer);
-    e.storage().instance().set(&listed_key, &listed);
+// Active listings in the order they were created
+pub fn get_active_listings(e: Env, cursor: u64, limit: u32) -> ListingPage {
+    internal_listing_page(&e, &IndexKind::Listings, cursor, limit)
+}
 
-    e.events()
-        .publish((Symbol::new(&e, "canceled_trade_offer"), seller), item_id);
+pub fn get_listings_by_seller(e: Env, seller: Address, cursor: u64, limit: u32) -> ListingPage {
+    internal_listing_page(&e, &IndexKind::SellerListings(seller), cursor, limit)
+}
+
+fn internal_index_offer(e: &Env, offer: &TradeOffer) -> Result<(), Error> {
+    index_add(e, &IndexKind::Listings, &offer.item_id);
+    index_add(
+        e,
+        &IndexKind::SellerListings(offer.seller.clone()),
+        &offer.item_id,
+    );
+
+    add_listing_price(
+        e,
+        &offer.item_id,
+        &offer.token,
+        offer.price,
+        offer.expires_at,
+    )
+}
+
+fn internal_unindex_offer(e: &Env, offer: &TradeOffer) -> Result<(), Error> {
+    index_remove(e, &IndexKind::Listings, &offer.item_id);
+    index_remove(
+        e,
+        &IndexKind::SellerListings(offer.seller.clone()),
+        &offer.item_id,
+    );
+
+    remove_listing_price(e, &offer.item_id, &offer.token)
+}
+
+// Hand the item back to the seller and drop the listing
+fn internal_return_to_seller(e: &Env, offer: &TradeOffer, reason: &[u8]) -> Result<(), Error> {
+    release_custody(e, &offer.item_id, &offer.seller, reason)?;
+
+    remove_trade_offer(e, &offer.item_id);
+    set_listed(e, &offer.item_id, false);
+    internal_unindex_offer(e, offer)?;
 
     Ok(())
 }
+
+fn internal_listing_page(e: &Env, kind: &IndexKind, cursor: u64, limit: u32) -> ListingPage {
+    let (item_ids, next_cursor) = index_page(e, kind, cursor, limit);
+
+    // Expired listings stay indexed until purged but can no longer be bought
+    let mut offers = Vec::new(e);
+    for item_id in item_ids.iter() {
+        if let Some(offer) = get_trade_offer(e, &item_id) {
+            if !internal_is_expired(e, &offer) {
+                offers.push_back(offer);
+            }
+        }
+    }
+
+    ListingPage {
+        offers,
+        next_cursor,
+    }
+}
+
+fn internal_is_expired(e: &Env, offer: &TradeOffer) -> bool {
+    match offer.expires_at {
+        Some(expiry) => expiry <= e.ledger().timestamp(),
+        None => false,
+    }
+}
@@ -1,31 +1,229 @@
-use soroban_sdk::{contracterror, contracttype, Address, BytesN, String};
+use soroban_sdk::{contracterror, contracttype, Address, Bytes, BytesN, Map, Vec};
 
+// Mirrors of the asset-registry types, decoded from `get_asset_info`
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
-pub struct GameItem {
-    pub item_id: String,
+pub struct GameAsset {
+    pub id: BytesN<32>,
     pub owner: Address,
-    pub metadata: BytesN<64>,
+    pub metadata: AssetMetadata,
+    pub history: Vec<OwnershipRecord>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AssetMetadata {
+    pub name: Bytes,
+    pub item_type: Bytes,
+    pub attributes: Map<Bytes, Bytes>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct OwnershipRecord {
+    pub previous_owner: Address,
+    pub timestamp: u64,
+    pub reason: Bytes,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
 pub struct TradeOffer {
-    pub item_id: String,
+    pub item_id: BytesN<32>,
     pub seller: Address,
     pub price: i128,
+    pub token: Address,
+    pub expires_at: Option<u64>,
     pub is_active: bool,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct BatchListing {
+    pub item_id: BytesN<32>,
+    pub price: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum BidIncrement {
+    AnyHigher,
+    Absolute(i128),
+    BasisPoints(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AuctionConfig {
+    // Not secret: it sits in contract storage like everything else, and a claim_bid
+    // that fails with ReserveNotMet tells everyone the winning bid fell short of it
+    pub reserve_price: Option<i128>,
+    pub min_increment: BidIncrement,
+    pub buy_now_price: Option<i128>,
+    // Seconds before `end_time` in which a bid extends the auction; 0 uses the marketplace default
+    pub extension_window: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct BuyOffer {
+    pub item_id: BytesN<32>,
+    pub buyer: Address,
+    pub price: i128,
+    pub token: Address,
+    pub expires_at: u64,
+}
+
+// The maker's items and tokens are held by the marketplace until the taker accepts
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SwapOffer {
+    pub swap_id: u64,
+    pub maker: Address,
+    pub taker: Address,
+    pub maker_items: Vec<BytesN<32>>,
+    pub taker_items: Vec<BytesN<32>>,
+    pub token: Address,
+    pub maker_amount: i128,
+    pub taker_amount: i128,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[contracttype]
 pub struct Auction {
-    pub item_id: String,
+    pub item_id: BytesN<32>,
     pub seller: Address,
+    pub token: Address,
     pub highest_bid: i128,
     pub highest_bidder: Address,
     pub end_time: u64,
     pub is_active: bool,
+    pub config: AuctionConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum AuctionSort {
+    Created,
+    EndTime,
+}
+
+// `next_cursor` is None once there is nothing left to page through
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ListingPage {
+    pub offers: Vec<TradeOffer>,
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AuctionPage {
+    pub auctions: Vec<Auction>,
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DutchAuction {
+    pub item_id: BytesN<32>,
+    pub seller: Address,
+    pub token: Address,
+    pub start_price: i128,
+    pub floor_price: i128,
+    // Price drops by `decay_amount` every `decay_interval` seconds until it hits the floor
+    pub decay_amount: i128,
+    pub decay_interval: u64,
+    pub start_time: u64,
+    pub is_active: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SealedPricing {
+    FirstPrice,
+    SecondPrice,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SealedPhase {
+    Commit,
+    Reveal,
+    Ended,
+}
+
+// Sell order signed off-chain by the seller's registered ed25519 key
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Order {
+    pub item_id: BytesN<32>,
+    pub seller: Address,
+    pub token: Address,
+    pub price: i128,
+    pub nonce: u64,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RentalTerms {
+    pub token: Address,
+    pub price_per_period: i128,
+    // Length of one rental period in seconds
+    pub period: u64,
+    pub max_periods: u32,
+    // Locked for the length of the rental and refunded when it ends, unless the admin
+    // forfeits it to the owner
+    pub collateral: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Rental {
+    pub item_id: BytesN<32>,
+    pub owner: Address,
+    pub terms: RentalTerms,
+    pub renter: Option<Address>,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SealedAuctionConfig {
+    pub token: Address,
+    pub reserve_price: i128,
+    pub deposit: i128,
+    pub commit_duration: u64,
+    pub reveal_duration: u64,
+    pub pricing: SealedPricing,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SealedAuction {
+    pub item_id: BytesN<32>,
+    pub seller: Address,
+    pub token: Address,
+    pub reserve_price: i128,
+    pub deposit: i128,
+    pub pricing: SealedPricing,
+    pub commit_end: u64,
+    pub reveal_end: u64,
+    pub highest_bid: i128,
+    pub highest_bidder: Address,
+    pub second_bid: i128,
+    pub is_active: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SealedBid {
+    // sha256(bid as 16 big-endian bytes || salt)
+    pub commitment: BytesN<32>,
+    pub locked: i128,
+    pub bid: i128,
+    pub revealed: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -34,9 +232,52 @@ pub struct TradeHistory {
     pub seller: Address,
     pub buyer: Address,
     pub price: i128,
+    pub platform_fee: i128,
+    pub royalty: i128,
+    pub seller_amount: i128,
     pub timestamp: u64,
 }
 
+// Running totals per item type and payment token. Volume is kept in hourly
+// and daily ring buckets indexed by `timestamp / width % len`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SalesStats {
+    pub last_sale_price: i128,
+    pub last_sale_at: u64,
+    pub trade_count: u64,
+    pub updated_at: u64,
+    pub hourly_volume: Vec<i128>,
+    pub daily_volume: Vec<i128>,
+}
+
+// One active listing in the per item type and token price heap
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct PriceEntry {
+    pub item_id: BytesN<32>,
+    pub price: i128,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CollectionStats {
+    pub last_sale_price: i128,
+    pub last_sale_at: u64,
+    pub trade_count: u64,
+    pub floor_price: Option<i128>,
+    pub volume_24h: i128,
+    pub volume_7d: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Royalty {
+    pub recipient: Address,
+    pub bps: u32,
+}
+
 #[contracterror]
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
 #[repr(u32)]
@@ -66,12 +307,87 @@ pub enum Error {
     ItemNotListed = 23,
     AuctionStillRunning = 24,
     BidAlreadyPlaced = 25,
+    ReserveNotMet = 26,
+    WrongAuctionPhase = 27,
+    AlreadyCommitted = 28,
+    CommitmentNotFound = 29,
+    CommitmentMismatch = 30,
+    AlreadyRevealed = 31,
+    FeeTooHigh = 32,
+    TokenNotAllowed = 33,
+    OfferExpired = 34,
+    OfferNotFound = 35,
+    OfferAlreadyExists = 36,
+    SwapNotFound = 37,
+    ListingExpired = 38,
+    ContractPaused = 39,
+    AddressBlocked = 40,
+    RentalNotFound = 41,
+    ItemRented = 42,
+    ItemNotRented = 43,
+    OrderExpired = 45,
+    OrderNonceUsed = 46,
+    OrderSignerNotSet = 47,
+    MixedPaymentTokens = 49,
+    TooManyBids = 50,
+    BidsNotClaimed = 51,
+    DuplicateItem = 52,
 }
 
+// Config lives in instance storage; per-item state gets its own persistent entry
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq, Copy)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     Admin,
+    AssetRegistry,
+    TradeOffer(BytesN<32>),
+    Listed(BytesN<32>),
+    Auction(BytesN<32>),
+    TradeHistory(BytesN<32>),
+    Escrow(Address, Address),
+    AllowedTokens,
+    AuctionExtension,
+    DutchAuction(BytesN<32>),
+    SealedAuction(BytesN<32>),
+    SealedBids(BytesN<32>),
+    PlatformFee,
+    Treasury,
+    ItemRoyalty(BytesN<32>),
+    CollectionRoyalty(Bytes),
+    BuyOffers(BytesN<32>),
+    Swap(u64),
+    NextSwapId,
+    IndexLen(IndexKind),
+    IndexAt(IndexKind, u64),
+    IndexSlot(IndexKind, BytesN<32>),
+    AuctionsEnding(u64),
+    LastEndBucket,
+    Paused,
+    Blocked(Address),
+    EscrowAvailable(Address, Address),
+    SalesStats(Bytes, Address),
+    PriceHeapLen(Bytes, Address),
+    PriceHeapAt(Bytes, Address, u32),
+    PriceSlot(BytesN<32>),
+    ListedType(BytesN<32>),
+    Rental(BytesN<32>),
+    OrderSigner(Address),
+    OrderNonce(Address, u64),
+    ItemSwap(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IndexKind {
+    Listings,
+    Auctions,
+    SellerListings(Address),
+}
+
+// Instance keys of the original contract, read only by `migrate_storage`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq, Copy)]
+pub enum LegacyDataKey {
     Items,
     TradeOffer,
     Listed,
@@ -85,3 +401,11 @@ pub enum DataKey {
 pub struct EscrowBalance {
     pub balance: i128,
 }
+
+// `available` can be withdrawn or spent on bids; `locked` backs open bids and offers
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct EscrowAccount {
+    pub available: i128,
+    pub locked: i128,
+}